@@ -1,4 +1,12 @@
-use clap::Parser;
+use crate::config::keys::{keys_help, CONFIG_KEYS};
+use crate::config::{overrides, Config, ConfigLoader};
+use crate::core::trace;
+use chrono::NaiveDate;
+use clap::builder::{PossibleValue, StringValueParser, TypedValueParser};
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use std::ffi::OsStr;
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(name = "CCometixLine (ccline)")]
@@ -15,12 +23,20 @@ use clap::Parser;
 )]
 pub struct Cli {
     /// Configuration file path
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub config: Option<String>,
 
-    /// Theme selection
-    #[arg(short, long, default_value = "dark")]
-    pub theme: String,
+    /// Theme selection (dark, light or plain), overriding the config file
+    #[arg(short, long, global = true)]
+    pub theme: Option<String>,
+
+    /// Override a config key for this run, e.g. `--set segments.git.show_sha=true` (repeatable)
+    #[arg(long = "set", value_name = "KEY=VALUE", global = true)]
+    pub set: Vec<String>,
+
+    /// Show only these segments, e.g. `--segments model,git,usage`
+    #[arg(long, value_name = "LIST", value_delimiter = ',', global = true)]
+    pub segments: Option<Vec<String>>,
 
     /// Enable TUI configuration mode
     #[arg(long)]
@@ -33,10 +49,190 @@ pub struct Cli {
     /// Validate configuration file
     #[arg(long)]
     pub validate: bool,
+
+    /// Append a trace of each render to ~/.claude/ccline/debug.jsonl (also enabled by CCLINE_LOG)
+    #[arg(long)]
+    pub debug: bool,
+
+    /// Render the payload in FILE instead of stdin and print the statusline with its trace
+    #[arg(long, value_name = "FILE")]
+    pub explain: Option<PathBuf>,
+
+    /// Save each stdin payload, transcript tail and git state under DIR for `ccline replay`
+    #[arg(long, value_name = "DIR")]
+    pub record: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Summarize token usage and cost from all Claude Code transcripts
+    Report(ReportArgs),
+    /// Render the statusline from sample data or a captured payload
+    Preview(PreviewArgs),
+    /// Render payloads captured with --record again
+    Replay(ReplayArgs),
+    /// Copy ccline into ~/.claude/ccline and set it as Claude Code's statusline
+    Install(InstallArgs),
+    /// Undo `ccline install`, restoring the previous statusline setting
+    Uninstall(InstallArgs),
+    /// Check settings.json, config, git, fonts, colors and render times
+    Doctor,
+    /// Print a shell completion script
+    Completions(CompletionsArgs),
+    /// Print the man page, or write one page per subcommand to a directory
+    Man(ManArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ReportArgs {
+    /// How to group usage
+    #[arg(short, long, value_enum, default_value_t = GroupBy::Day)]
+    pub by: GroupBy,
+
+    /// First day to include (YYYY-MM-DD, local time)
+    #[arg(long, value_parser = parse_date)]
+    pub since: Option<NaiveDate>,
+
+    /// Last day to include (YYYY-MM-DD, local time)
+    #[arg(long, value_parser = parse_date)]
+    pub until: Option<NaiveDate>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = ReportFormat::Table)]
+    pub format: ReportFormat,
+}
+
+#[derive(Args, Debug)]
+pub struct PreviewArgs {
+    /// Render this captured stdin payload instead of the built-in sample
+    #[arg(short, long, value_name = "FILE")]
+    pub input: Option<PathBuf>,
+
+    /// Render once with every theme
+    #[arg(long)]
+    pub all_themes: bool,
+
+    /// Also show the statusline cut to this many columns (repeatable)
+    #[arg(short, long, value_name = "COLUMNS")]
+    pub width: Vec<usize>,
+}
+
+#[derive(Args, Debug)]
+pub struct ReplayArgs {
+    /// A capture directory, or the --record directory holding several
+    pub dir: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct InstallArgs {
+    /// Edit .claude/settings.json of the current project instead of ~/.claude/settings.json
+    #[arg(long)]
+    pub project: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct CompletionsArgs {
+    #[arg(value_enum)]
+    pub shell: Shell,
+}
+
+#[derive(Args, Debug)]
+pub struct ManArgs {
+    /// Write ccline.1 and ccline-<subcommand>.1 into this directory instead of printing
+    #[arg(long, value_name = "DIR")]
+    pub out_dir: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupBy {
+    Day,
+    Week,
+    Month,
+    Project,
+    Model,
+    Session,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("expected a date like 2025-08-12, got `{}`", value))
 }
 
 impl Cli {
     pub fn parse_args() -> Self {
-        Self::parse()
+        let matches = Self::command_with_keys().get_matches();
+        Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit())
+    }
+
+    /// The config file (or `--config`) with `--theme`, `--segments` and `--set` applied,
+    /// along with a message for each of them that couldn't be applied.
+    pub fn load_config(&self) -> (Config, Vec<String>) {
+        let mut problems = Vec::new();
+        let mut config = match &self.config {
+            Some(path) => ConfigLoader::load_from_path(path).unwrap_or_else(|e| {
+                problems.push(format!("failed to load config {}: {}", path, e));
+                Config::default()
+            }),
+            None => ConfigLoader::load(),
+        };
+        if let Some(theme) = &self.theme {
+            config.theme = theme.clone();
+        }
+        if let Some(segments) = &self.segments {
+            if let Err(e) = overrides::apply_segments(&mut config, segments) {
+                problems.push(format!("--segments: {}", e));
+            }
+        }
+        for assignment in &self.set {
+            match overrides::apply_override(&mut config, assignment) {
+                Ok(()) => trace::event("config", || format!("--set {}", assignment)),
+                Err(e) => problems.push(format!("--set: {}", e)),
+            }
+        }
+        (config, problems)
+    }
+
+    /// The clap command with the config keys appended to the long help and offered as
+    /// completions for `--set`.
+    pub fn command_with_keys() -> clap::Command {
+        Self::command()
+            .after_long_help(keys_help())
+            .mut_arg("set", |arg| {
+                arg.value_parser(SetValueParser).hide_possible_values(true)
+            })
+    }
+}
+
+/// Takes any `--set` value as is, but lists `KEY=` for every config key so shell
+/// completions can offer them.
+#[derive(Clone)]
+struct SetValueParser;
+
+impl TypedValueParser for SetValueParser {
+    type Value = String;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> Result<String, clap::Error> {
+        StringValueParser::new().parse_ref(cmd, arg, value)
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(CONFIG_KEYS.iter().map(|(key, description)| {
+            PossibleValue::new(format!("{}=", key)).help(*description)
+        })))
     }
 }
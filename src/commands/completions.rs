@@ -0,0 +1,47 @@
+use crate::cli::{Cli, CompletionsArgs, ManArgs};
+use std::io;
+
+/// Name the scripts and pages use; the package builds `ccometixline`, installs as `ccline`.
+const BIN_NAME: &str = "ccline";
+
+pub fn completions(args: &CompletionsArgs) -> io::Result<()> {
+    let mut command = Cli::command_with_keys();
+    clap_complete::generate(args.shell, &mut command, BIN_NAME, &mut io::stdout());
+    Ok(())
+}
+
+pub fn man(args: &ManArgs) -> io::Result<()> {
+    let command = Cli::command_with_keys().name(BIN_NAME).bin_name(BIN_NAME);
+    match &args.out_dir {
+        Some(dir) => {
+            std::fs::create_dir_all(dir)?;
+            clap_mangen::generate_to(command, dir)?;
+            println!("Wrote man pages to {}", dir.display());
+            Ok(())
+        }
+        None => clap_mangen::Man::new(command).render(&mut io::stdout()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::FromArgMatches;
+    use clap_complete::Shell;
+
+    #[test]
+    fn test_set_completes_config_keys() {
+        let mut script = Vec::new();
+        let mut command = Cli::command_with_keys();
+        clap_complete::generate(Shell::Fish, &mut command, BIN_NAME, &mut script);
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains("segments.git.show_sha="));
+
+        // The candidates don't restrict what `--set` accepts
+        let matches = Cli::command_with_keys()
+            .try_get_matches_from(["ccline", "--set", "segments.git.show_sha=true"])
+            .unwrap();
+        let cli = Cli::from_arg_matches(&matches).unwrap();
+        assert_eq!(cli.set, ["segments.git.show_sha=true"]);
+    }
+}
@@ -0,0 +1,415 @@
+use super::install::resolve_program;
+use super::preview::SampleSession;
+use crate::cli::Cli;
+use crate::config::{Config, ConfigLoader, InputData};
+use crate::core::theme::Theme;
+use crate::core::{trace, transcript, StatusLineGenerator};
+use serde_json::Value;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A segment slower than this makes the statusline feel laggy.
+const SLOW_SEGMENT_MS: f64 = 50.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Ok,
+    Info,
+    Warn,
+    Fail,
+}
+
+struct Check {
+    status: Status,
+    name: &'static str,
+    detail: String,
+    /// What to do about a warning or failure
+    fix: Option<String>,
+}
+
+impl Check {
+    fn new(status: Status, name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            status,
+            name,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+pub fn run(cli: &Cli) -> io::Result<()> {
+    let mut checks = check_settings();
+    checks.push(check_config(cli.config.as_deref()));
+    // Everything else is checked with the config the statusline renders with
+    let (config, problems) = cli.load_config();
+    checks.extend(problems.into_iter().map(|problem| {
+        Check::new(Status::Fail, "overrides", problem)
+            .with_fix("fix the flag in the statusLine command; `ccline --help` lists the keys")
+    }));
+    checks.extend(check_theme(&config));
+    checks.push(check_git());
+    checks.push(check_transcripts());
+    checks.push(check_colors(&config));
+    checks.push(check_nerd_font());
+    checks.extend(check_render_times(config)?);
+
+    for check in &checks {
+        let mark = match check.status {
+            Status::Ok => "\x1b[32m✓\x1b[0m",
+            Status::Info => "\x1b[36mi\x1b[0m",
+            Status::Warn => "\x1b[33m!\x1b[0m",
+            Status::Fail => "\x1b[31m✗\x1b[0m",
+        };
+        println!("{} {:<14} {}", mark, check.name, check.detail);
+        if let Some(fix) = &check.fix {
+            println!("  {:<14} → {}", "", fix);
+        }
+    }
+
+    let failed = checks
+        .iter()
+        .filter(|check| check.status == Status::Fail)
+        .count();
+    if failed > 0 {
+        return Err(io::Error::other(format!("{} check(s) failed", failed)));
+    }
+    Ok(())
+}
+
+/// Whether Claude Code's user and project settings run ccline as the statusline.
+fn check_settings() -> Vec<Check> {
+    let mut locations = Vec::new();
+    if let Some(home) = dirs::home_dir() {
+        locations.push(home.join(".claude").join("settings.json"));
+    }
+    if let Ok(dir) = env::current_dir() {
+        locations.push(dir.join(".claude").join("settings.json"));
+    }
+
+    let mut checks: Vec<Check> = locations
+        .iter()
+        .filter_map(|path| check_settings_file(path))
+        .collect();
+    if checks.is_empty() {
+        checks.push(
+            Check::new(
+                Status::Fail,
+                "settings.json",
+                "no statusLine command in ~/.claude/settings.json or .claude/settings.json",
+            )
+            .with_fix("run `ccline install`"),
+        );
+    }
+    checks
+}
+
+/// The statusline command in one settings file, `None` if the file or the command is missing.
+fn check_settings_file(path: &Path) -> Option<Check> {
+    let content = fs::read_to_string(path).ok()?;
+    let settings: Value = match serde_json::from_str(&content) {
+        Ok(settings) => settings,
+        Err(e) => {
+            return Some(
+                Check::new(
+                    Status::Fail,
+                    "settings.json",
+                    format!("{}: {}", path.display(), e),
+                )
+                .with_fix("fix the JSON syntax; Claude Code ignores a file it can't parse"),
+            );
+        }
+    };
+    let command = settings
+        .pointer("/statusLine/command")
+        .and_then(Value::as_str)?;
+
+    let program = command.split_whitespace().next().unwrap_or_default();
+    let resolved = resolve_program(program);
+    let check = if !resolved.is_file() {
+        Check::new(
+            Status::Fail,
+            "settings.json",
+            format!("{} runs `{}`, which doesn't exist", path.display(), program),
+        )
+        .with_fix("run `ccline install` to copy the binary and update the command")
+    } else if !resolved
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().contains("ccline"))
+    {
+        Check::new(
+            Status::Warn,
+            "settings.json",
+            format!("{} runs `{}`, not ccline", path.display(), command),
+        )
+        .with_fix("run `ccline install` to switch the statusline to ccline")
+    } else {
+        Check::new(
+            Status::Ok,
+            "settings.json",
+            format!("{} runs {}", path.display(), resolved.display()),
+        )
+    };
+    Some(check)
+}
+
+fn check_config(path: Option<&str>) -> Check {
+    let path = match path {
+        Some(path) => Some(PathBuf::from(path)),
+        None => ConfigLoader::default_path().filter(|path| path.exists()),
+    };
+    let Some(path) = path else {
+        return Check::new(Status::Ok, "config", "no config file, using defaults");
+    };
+
+    match ConfigLoader::load_from_path(&path) {
+        Ok(_) => Check::new(Status::Ok, "config", format!("{} is valid", path.display())),
+        Err(e) => Check::new(Status::Fail, "config", format!("{}: {}", path.display(), e))
+            .with_fix("fix the file or compare it with `ccline --print-config`"),
+    }
+}
+
+/// A theme set in the config file or with `--theme` that doesn't exist.
+fn check_theme(config: &Config) -> Option<Check> {
+    Theme::by_name(&config.theme).is_none().then(|| {
+        Check::new(
+            Status::Warn,
+            "config",
+            format!("unknown theme `{}`", config.theme),
+        )
+        .with_fix("set `theme` to dark, light or plain")
+    })
+}
+
+fn check_git() -> Check {
+    match Command::new("git").arg("--version").output() {
+        Ok(output) if output.status.success() => Check::new(
+            Status::Ok,
+            "git",
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ),
+        Ok(output) => Check::new(
+            Status::Warn,
+            "git",
+            format!("`git --version` failed: {}", output.status),
+        )
+        .with_fix("check your git installation; the git segment stays empty without it"),
+        Err(e) => Check::new(Status::Warn, "git", format!("git not found: {}", e))
+            .with_fix("install git and make sure it is on PATH to see branch and status"),
+    }
+}
+
+fn check_transcripts() -> Check {
+    let Some(dir) = transcript::projects_dir() else {
+        return Check::new(Status::Warn, "transcripts", "home directory not found");
+    };
+    match fs::read_dir(&dir) {
+        Ok(_) => {
+            let count = transcript::find_transcripts(None).len();
+            Check::new(
+                Status::Ok,
+                "transcripts",
+                format!("{} readable, {} transcripts", dir.display(), count),
+            )
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Check::new(
+            Status::Info,
+            "transcripts",
+            format!("{} doesn't exist yet", dir.display()),
+        )
+        .with_fix("start a Claude Code session; usage and cost history are read from here"),
+        Err(e) => Check::new(
+            Status::Fail,
+            "transcripts",
+            format!("{}: {}", dir.display(), e),
+        )
+        .with_fix("make the directory readable by your user"),
+    }
+}
+
+fn check_colors(config: &Config) -> Check {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    let term = env::var("TERM").unwrap_or_default();
+    let truecolor =
+        env::var("COLORTERM").is_ok_and(|value| value == "truecolor" || value == "24bit");
+
+    if (no_color || term == "dumb") && config.theme != "plain" {
+        let reason = if no_color {
+            "NO_COLOR is set"
+        } else {
+            "TERM=dumb"
+        };
+        return Check::new(
+            Status::Warn,
+            "colors",
+            format!("{} but theme `{}` uses colors", reason, config.theme),
+        )
+        .with_fix("set `theme = \"plain\"` in the config");
+    }
+    if config.theme == "light" && !term.contains("256color") && !truecolor {
+        return Check::new(
+            Status::Warn,
+            "colors",
+            format!("theme `light` uses 256 colors, TERM={}", term),
+        )
+        .with_fix("use a 256-color terminal or the `dark` theme");
+    }
+    let support = if truecolor {
+        "truecolor"
+    } else if term.contains("256color") {
+        "256 colors"
+    } else {
+        "basic colors"
+    };
+    Check::new(
+        Status::Ok,
+        "colors",
+        format!("{} (TERM={}), theme `{}`", support, term, config.theme),
+    )
+}
+
+/// Glyphs only a Nerd Font has; the terminal can't be asked which font it uses.
+fn check_nerd_font() -> Check {
+    Check::new(
+        Status::Info,
+        "Nerd Font",
+        "\u{e26d} \u{f024b} \u{f02a2} \u{f49b} \u{f09d3} \u{f440}  ← these should be icons",
+    )
+    .with_fix("if they show as boxes or question marks, install a Nerd Font and select it in your terminal")
+}
+
+/// Render the sample session and time each segment; slow ones get a warning.
+fn check_render_times(config: Config) -> io::Result<Vec<Check>> {
+    let sample = SampleSession::create()?;
+    let (input, _) = InputData::parse_lenient(&sample.payload());
+
+    trace::enable();
+    trace::take();
+    StatusLineGenerator::new(config).generate(&input);
+    let events = trace::take();
+
+    let mut checks = Vec::new();
+    let mut total_ms = 0.0;
+    for event in events.iter().filter(|event| event.kind == "segment") {
+        let ms = event.duration_us.unwrap_or(0) as f64 / 1000.0;
+        total_ms += ms;
+        let name = event.message.split_whitespace().next().unwrap_or_default();
+        let check = Check::new(Status::Ok, "render", format!("{} took {:.1}ms", name, ms));
+        checks.push(if ms > SLOW_SEGMENT_MS {
+            Check {
+                status: Status::Warn,
+                ..check.with_fix(slow_segment_fix(name))
+            }
+        } else {
+            check
+        });
+    }
+    checks.push(Check::new(
+        Status::Ok,
+        "render",
+        format!("all segments rendered in {:.1}ms", total_ms),
+    ));
+    Ok(checks)
+}
+
+fn slow_segment_fix(segment: &str) -> &'static str {
+    match segment {
+        "git" => "large repositories make `git status` slow; consider `segments.git = false`",
+        "spend" | "block" => {
+            "these read the usage index; the first run builds it from every transcript, later runs only re-read changed ones"
+        }
+        _ => "disable the segment if you don't need it",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("ccline_doctor_{}_{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_check_settings_file() {
+        let dir = temp_dir("settings");
+        let ccline = dir.join("ccline");
+        fs::write(&ccline, "").unwrap();
+        let other = dir.join("statusline.sh");
+        fs::write(&other, "").unwrap();
+        let status = |content: &str| {
+            let path = dir.join("settings.json");
+            fs::write(&path, content).unwrap();
+            check_settings_file(&path).map(|check| check.status)
+        };
+        let command = |program: &Path| {
+            serde_json::json!({ "statusLine": { "command": format!("{} --debug", program.display()) } })
+                .to_string()
+        };
+
+        let ok = status(&command(&ccline));
+        let foreign = status(&command(&other));
+        let missing = status(&command(&dir.join("gone").join("ccline")));
+        let invalid = status("{ not json");
+        let unset = status(r#"{"model": "opus"}"#);
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(ok, Some(Status::Ok));
+        assert_eq!(foreign, Some(Status::Warn));
+        assert_eq!(missing, Some(Status::Fail));
+        assert_eq!(invalid, Some(Status::Fail));
+        assert_eq!(unset, None);
+        assert_eq!(
+            check_settings_file(&dir.join("settings.json")).map(|c| c.status),
+            None
+        );
+    }
+
+    #[test]
+    fn test_check_config() {
+        let dir = temp_dir("config");
+        let path = dir.join("config.toml");
+        let check = |content: &str| {
+            fs::write(&path, content).unwrap();
+            check_config(Some(&path.to_string_lossy())).status
+        };
+
+        let valid = check("theme = \"light\"\n");
+        let invalid = check("theme = [\n");
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(valid, Status::Ok);
+        assert_eq!(invalid, Status::Fail);
+    }
+
+    #[test]
+    fn test_checks_use_command_line_overrides() {
+        let cli = Cli::try_parse_from([
+            "ccline",
+            "--theme",
+            "solarized",
+            "--set",
+            "segments.usage.bar_width=wide",
+        ])
+        .unwrap();
+        let (config, problems) = cli.load_config();
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(
+            check_theme(&config).map(|check| check.status),
+            Some(Status::Warn)
+        );
+        assert!(check_theme(&Config::default()).is_none());
+    }
+}
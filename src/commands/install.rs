@@ -0,0 +1,695 @@
+use crate::cli::InstallArgs;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::env;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+const BACKUP_SUFFIX: &str = "ccline-backup";
+
+/// Settings files `install --project` wrote to, one path per line, next to the binary.
+const PROJECTS_FILE: &str = "projects.txt";
+
+/// How project settings, shared with the whole team, name the binary: each teammate has
+/// their own copy under their home directory.
+const PORTABLE_BINARY: &str = if cfg!(windows) {
+    "%USERPROFILE%\\.claude\\ccline\\ccline.exe"
+} else {
+    "~/.claude/ccline/ccline"
+};
+
+pub fn install(args: &InstallArgs) -> io::Result<()> {
+    let binary = install_binary()?;
+    println!("Installed {}", binary.display());
+
+    let settings_path = settings_path(args)?;
+    let program = if args.project {
+        PORTABLE_BINARY.to_string()
+    } else {
+        binary.to_string_lossy().into_owned()
+    };
+    install_settings(&settings_path, &program, &binary)?;
+
+    if args.project {
+        let mut projects = project_installs()?;
+        if !projects.contains(&settings_path) {
+            projects.push(settings_path);
+            save_project_installs(&projects)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn uninstall(args: &InstallArgs) -> io::Result<()> {
+    let settings_path = settings_path(args)?;
+    let binary = binary_path()?;
+    // Someone else's statusline is left alone, and so is everything it may rely on
+    if !restore_settings(&settings_path, &binary)? {
+        return Ok(());
+    }
+
+    let mut projects = project_installs()?;
+    if args.project {
+        projects.retain(|path| *path != settings_path);
+        return save_project_installs(&projects);
+    }
+
+    // Project installs share the binary with the user-level one
+    let still_used: Vec<PathBuf> = projects
+        .into_iter()
+        .filter(|path| {
+            fs::read_to_string(path).is_ok_and(|settings| runs_binary(&settings, &binary))
+        })
+        .collect();
+    if !still_used.is_empty() {
+        println!("Keeping {}, still used by:", binary.display());
+        for path in &still_used {
+            println!("  {}", path.display());
+        }
+        println!("Run `ccline uninstall --project` in those projects to remove it");
+        return save_project_installs(&still_used);
+    }
+    if binary.exists() {
+        fs::remove_file(&binary)?;
+        println!("Removed {}", binary.display());
+    }
+    Ok(())
+}
+
+/// Back up `settings_path` and point its `statusLine` at `program`.
+///
+/// A statusline that already runs `binary` keeps its flags, so a reinstall doesn't drop
+/// the options it was set up with.
+fn install_settings(settings_path: &Path, program: &str, binary: &Path) -> io::Result<()> {
+    let original = match fs::read_to_string(settings_path) {
+        Ok(content) => Some(content),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+
+    // Keep the first backup: after a reinstall it still holds the pre-ccline settings
+    let backup = backup_path(settings_path);
+    if let Some(original) = &original {
+        if !backup.exists() {
+            fs::write(&backup, original)?;
+            println!(
+                "Backed up {} to {}",
+                settings_path.display(),
+                backup.display()
+            );
+        }
+    }
+
+    let flags = original
+        .as_deref()
+        .filter(|settings| runs_binary(settings, binary))
+        .and_then(status_line_command)
+        .and_then(|command| {
+            let (_, flags) = command.trim().split_once(char::is_whitespace)?;
+            Some(flags.trim().to_string())
+        });
+    let command = match flags {
+        Some(flags) => format!("{} {}", program, flags),
+        None => program.to_string(),
+    };
+    let updated = merge_status_line(original.as_deref().unwrap_or_default(), &command)?;
+    if let Some(parent) = settings_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_atomic(settings_path, &updated)?;
+    println!("Set statusLine in {}", settings_path.display());
+    Ok(())
+}
+
+/// Put back the `statusLine` of `settings_path` from its backup and remove the backup.
+///
+/// Returns `false`, changing nothing, when the statusline doesn't run `binary`.
+fn restore_settings(settings_path: &Path, binary: &Path) -> io::Result<bool> {
+    let backup = backup_path(settings_path);
+    match fs::read_to_string(settings_path) {
+        Ok(current) if !runs_binary(&current, binary) => {
+            println!(
+                "statusLine in {} doesn't run ccline, leaving it unchanged",
+                settings_path.display()
+            );
+            return Ok(false);
+        }
+        Ok(current) => {
+            let backup_content = fs::read_to_string(&backup).ok();
+            let restored = restore_status_line(&current, backup_content.as_deref())?;
+            write_atomic(settings_path, &restored)?;
+            println!("Restored statusLine in {}", settings_path.display());
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    if backup.exists() {
+        fs::remove_file(&backup)?;
+    }
+    Ok(true)
+}
+
+/// Replace `path` with `content` through a temporary file, so Claude Code never reads a
+/// half-written settings file.
+fn write_atomic(path: &Path, content: &str) -> io::Result<()> {
+    let staging = path.with_extension(format!("json.{}.tmp", std::process::id()));
+    let result = fs::write(&staging, content).and_then(|()| fs::rename(&staging, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&staging);
+    }
+    result
+}
+
+/// Set `statusLine` to run `command`.
+///
+/// Only the `statusLine` value is written; every other byte of the file is left as it was.
+pub fn merge_status_line(settings: &str, command: &str) -> io::Result<String> {
+    let mut root = parse_object(settings)?;
+    let status_line = serde_json::json!({
+        "type": "command",
+        "command": command,
+        "padding": 0
+    });
+    // With nothing else in the file there is no formatting to keep
+    if root.is_empty() {
+        root.insert("statusLine".to_string(), status_line);
+        return to_json(&root, settings);
+    }
+
+    let members = Members::scan(settings)?;
+    let value = if members.multiline(settings) {
+        let mut out = Vec::new();
+        let unit = indent_unit(settings);
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(unit.as_bytes());
+        let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+        status_line.serialize(&mut serializer)?;
+        let pretty = String::from_utf8_lossy(&out).into_owned();
+        pretty.replace('\n', &format!("\n{}", members.indent(settings)))
+    } else {
+        status_line.to_string()
+    };
+    Ok(members.set(settings, "statusLine", Some(&value)))
+}
+
+/// Put back the `statusLine` from before `ccline install`, or remove it if there was none.
+///
+/// Only `statusLine` comes from the backup, copied as it was written there; anything else
+/// changed since install is kept.
+pub fn restore_status_line(settings: &str, backup: Option<&str>) -> io::Result<String> {
+    parse_object(settings)?;
+    let previous = match backup {
+        Some(backup) if !backup.trim().is_empty() => {
+            parse_object(backup)?;
+            Members::scan(backup)?
+                .get("statusLine")
+                .map(|member| backup[member.value.clone()].to_string())
+        }
+        _ => None,
+    };
+    if settings.trim().is_empty() {
+        return Ok(settings.to_string());
+    }
+    Ok(Members::scan(settings)?.set(settings, "statusLine", previous.as_deref()))
+}
+
+/// Where the members of a JSON object are in its text, so one can be edited in place.
+struct Members {
+    /// Byte offsets of the object's `{` and `}`
+    open: usize,
+    close: usize,
+    entries: Vec<Member>,
+}
+
+struct Member {
+    key: String,
+    /// Offset of the key's opening quote
+    start: usize,
+    value: Range<usize>,
+}
+
+impl Members {
+    /// Scan text already known to hold a JSON object.
+    fn scan(text: &str) -> io::Result<Self> {
+        let bytes = text.as_bytes();
+        let mut i = skip_whitespace(bytes, 0);
+        if bytes.get(i) != Some(&b'{') {
+            return Err(invalid_json());
+        }
+        let open = i;
+        let mut entries = Vec::new();
+        i += 1;
+        loop {
+            i = skip_whitespace(bytes, i);
+            match bytes.get(i) {
+                Some(b'}') => break,
+                Some(b'"') => {}
+                _ => return Err(invalid_json()),
+            }
+            let start = i;
+            i = skip_string(bytes, i)?;
+            let key: String = serde_json::from_str(&text[start..i])?;
+            i = skip_whitespace(bytes, i);
+            if bytes.get(i) != Some(&b':') {
+                return Err(invalid_json());
+            }
+            let value_start = skip_whitespace(bytes, i + 1);
+            i = skip_value(bytes, value_start)?;
+            entries.push(Member {
+                key,
+                start,
+                value: value_start..i,
+            });
+            i = skip_whitespace(bytes, i);
+            match bytes.get(i) {
+                Some(b',') => i += 1,
+                Some(b'}') => break,
+                _ => return Err(invalid_json()),
+            }
+        }
+        Ok(Self {
+            open,
+            close: i,
+            entries,
+        })
+    }
+
+    /// The member named `key`; like serde_json, the last one wins when a key repeats.
+    fn get(&self, key: &str) -> Option<&Member> {
+        self.entries.iter().rev().find(|member| member.key == key)
+    }
+
+    fn multiline(&self, text: &str) -> bool {
+        text[self.open..self.close].contains('\n')
+    }
+
+    /// Leading whitespace of the members' lines.
+    fn indent<'a>(&self, text: &'a str) -> &'a str {
+        match self.entries.first() {
+            Some(member) => {
+                let line_start = text[..member.start].rfind('\n').map_or(0, |at| at + 1);
+                let indent = &text[line_start..member.start];
+                if indent.trim().is_empty() {
+                    indent
+                } else {
+                    ""
+                }
+            }
+            None => indent_unit(text),
+        }
+    }
+
+    /// `text` with `key` set to the JSON `value`, or removed when `value` is `None`.
+    fn set(&self, text: &str, key: &str, value: Option<&str>) -> String {
+        let index = self.entries.iter().rposition(|member| member.key == key);
+        let mut out = text.to_string();
+        match (index, value) {
+            (Some(index), Some(value)) => {
+                out.replace_range(self.entries[index].value.clone(), value);
+            }
+            (Some(index), None) => {
+                let range = if index > 0 {
+                    self.entries[index - 1].value.end..self.entries[index].value.end
+                } else if let Some(next) = self.entries.get(1) {
+                    self.entries[0].start..next.start
+                } else {
+                    self.open + 1..self.close
+                };
+                out.replace_range(range, "");
+            }
+            (None, Some(value)) => {
+                let key = serde_json::to_string(key).unwrap_or_default();
+                let multiline = self.multiline(text);
+                let indent = self.indent(text);
+                match self.entries.last() {
+                    Some(last) if multiline => {
+                        out.insert_str(last.value.end, &format!(",\n{}{}: {}", indent, key, value))
+                    }
+                    Some(last) => out.insert_str(last.value.end, &format!(",{}:{}", key, value)),
+                    None => out.replace_range(
+                        self.open + 1..self.close,
+                        &format!("\n{}{}: {}\n", indent, key, value),
+                    ),
+                }
+            }
+            (None, None) => {}
+        }
+        out
+    }
+}
+
+fn skip_whitespace(bytes: &[u8], mut i: usize) -> usize {
+    while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+        i += 1;
+    }
+    i
+}
+
+/// Offset just past the string starting at `i`.
+fn skip_string(bytes: &[u8], mut i: usize) -> io::Result<usize> {
+    i += 1;
+    while let Some(&byte) = bytes.get(i) {
+        match byte {
+            b'\\' => i += 2,
+            b'"' => return Ok(i + 1),
+            _ => i += 1,
+        }
+    }
+    Err(invalid_json())
+}
+
+/// Offset just past the value starting at `i`.
+fn skip_value(bytes: &[u8], mut i: usize) -> io::Result<usize> {
+    match bytes.get(i) {
+        Some(b'"') => skip_string(bytes, i),
+        Some(b'{') | Some(b'[') => {
+            let mut depth = 0;
+            while let Some(&byte) = bytes.get(i) {
+                match byte {
+                    b'"' => {
+                        i = skip_string(bytes, i)?;
+                        continue;
+                    }
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Ok(i + 1);
+                        }
+                    }
+                    _ => {}
+                }
+                i += 1;
+            }
+            Err(invalid_json())
+        }
+        Some(_) => {
+            while bytes.get(i).is_some_and(|byte| {
+                !matches!(byte, b',' | b'}' | b']') && !byte.is_ascii_whitespace()
+            }) {
+                i += 1;
+            }
+            Ok(i)
+        }
+        None => Err(invalid_json()),
+    }
+}
+
+fn invalid_json() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "settings.json is not a JSON object",
+    )
+}
+
+fn status_line_command(settings: &str) -> Option<String> {
+    let root = parse_object(settings).ok()?;
+    let command = root.get("statusLine")?.get("command")?.as_str()?;
+    Some(command.to_string())
+}
+
+/// Whether the statusline command runs `binary`, whatever arguments follow and however
+/// the path is written (`~/.claude/ccline/ccline`, `$HOME/...` or absolute).
+fn runs_binary(settings: &str, binary: &Path) -> bool {
+    let Some(command) = status_line_command(settings) else {
+        return false;
+    };
+    let program = command.split_whitespace().next().unwrap_or_default();
+    resolve_program(program) == binary
+}
+
+/// Where Claude Code's shell finds `program`: `~/x` and `$HOME/x` expanded, bare names on PATH.
+pub fn resolve_program(program: &str) -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_default();
+    for prefix in ["~/", "$HOME/", "%USERPROFILE%\\"] {
+        if let Some(rest) = program.strip_prefix(prefix) {
+            return home.join(rest);
+        }
+    }
+
+    let path = Path::new(program);
+    if path.components().count() == 1 {
+        let on_path = env::var_os("PATH")
+            .iter()
+            .flat_map(env::split_paths)
+            .map(|dir| dir.join(program))
+            .find(|candidate| candidate.is_file());
+        if let Some(found) = on_path {
+            return found;
+        }
+    }
+    path.to_path_buf()
+}
+
+fn parse_object(content: &str) -> io::Result<Map<String, Value>> {
+    if content.trim().is_empty() {
+        return Ok(Map::new());
+    }
+    match serde_json::from_str(content)? {
+        Value::Object(map) => Ok(map),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "settings.json is not a JSON object",
+        )),
+    }
+}
+
+/// Pretty-print a new settings file.
+fn to_json(root: &Map<String, Value>, original: &str) -> io::Result<String> {
+    let mut out = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent_unit(original).as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+    root.serialize(&mut serializer)?;
+
+    let mut json =
+        String::from_utf8(out).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    if original.is_empty() || original.ends_with('\n') {
+        json.push('\n');
+    }
+    Ok(json)
+}
+
+/// One level of indentation in `text`, two spaces if it can't be told.
+fn indent_unit(text: &str) -> &str {
+    text.lines()
+        .skip(1)
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .find(|indent| !indent.is_empty())
+        .unwrap_or("  ")
+}
+
+/// Copy the running executable to `~/.claude/ccline/`.
+fn install_binary() -> io::Result<PathBuf> {
+    let current = env::current_exe()?;
+    let target = binary_path()?;
+    if current == target {
+        return Ok(target);
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Copy next to the target and rename, which also works while the old binary is running
+    let staging = target.with_extension("new");
+    fs::copy(&current, &staging)?;
+    fs::rename(&staging, &target)?;
+    Ok(target)
+}
+
+fn binary_path() -> io::Result<PathBuf> {
+    let name = if cfg!(windows) {
+        "ccline.exe"
+    } else {
+        "ccline"
+    };
+    Ok(claude_dir()?.join("ccline").join(name))
+}
+
+fn project_installs() -> io::Result<Vec<PathBuf>> {
+    match fs::read_to_string(claude_dir()?.join("ccline").join(PROJECTS_FILE)) {
+        Ok(content) => Ok(content.lines().map(PathBuf::from).collect()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+fn save_project_installs(projects: &[PathBuf]) -> io::Result<()> {
+    let path = claude_dir()?.join("ccline").join(PROJECTS_FILE);
+    if projects.is_empty() {
+        return match fs::remove_file(&path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+    let lines: Vec<String> = projects
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    fs::write(path, lines.join("\n") + "\n")
+}
+
+fn settings_path(args: &InstallArgs) -> io::Result<PathBuf> {
+    if args.project {
+        Ok(env::current_dir()?.join(".claude").join("settings.json"))
+    } else {
+        Ok(claude_dir()?.join("settings.json"))
+    }
+}
+
+fn backup_path(settings_path: &Path) -> PathBuf {
+    let mut name = settings_path.as_os_str().to_owned();
+    name.push(".");
+    name.push(BACKUP_SUFFIX);
+    PathBuf::from(name)
+}
+
+fn claude_dir() -> io::Result<PathBuf> {
+    dirs::home_dir()
+        .map(|home| home.join(".claude"))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "home directory not found"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTINGS: &str = r#"{
+    "model": "opus",
+    "statusLine": {
+        "type": "command",
+        "command": "~/bin/old-statusline"
+    },
+    "permissions": {
+        "allow": ["Bash(git status)"]
+    }
+}
+"#;
+
+    #[test]
+    fn test_merge_only_touches_status_line() {
+        let merged = merge_status_line(SETTINGS, "/home/me/.claude/ccline/ccline").unwrap();
+        assert_eq!(
+            merged,
+            r#"{
+    "model": "opus",
+    "statusLine": {
+        "type": "command",
+        "command": "/home/me/.claude/ccline/ccline",
+        "padding": 0
+    },
+    "permissions": {
+        "allow": ["Bash(git status)"]
+    }
+}
+"#
+        );
+
+        assert_eq!(
+            restore_status_line(&merged, Some(SETTINGS)).unwrap(),
+            SETTINGS
+        );
+        assert_eq!(
+            restore_status_line(&merged, None).unwrap(),
+            r#"{
+    "model": "opus",
+    "permissions": {
+        "allow": ["Bash(git status)"]
+    }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_merge_appends_status_line() {
+        let settings = "{\n  \"model\": \"opus\", \"env\": {\"A\": \"}\\\"\"}\n}";
+        let merged = merge_status_line(settings, "ccline").unwrap();
+        assert_eq!(
+            merged,
+            "{\n  \"model\": \"opus\", \"env\": {\"A\": \"}\\\"\"},\n  \"statusLine\": {\n    \"type\": \"command\",\n    \"command\": \"ccline\",\n    \"padding\": 0\n  }\n}"
+        );
+        assert_eq!(restore_status_line(&merged, None).unwrap(), settings);
+
+        let compact = merge_status_line(r#"{"model":"opus"}"#, "ccline").unwrap();
+        assert_eq!(
+            compact,
+            r#"{"model":"opus","statusLine":{"type":"command","command":"ccline","padding":0}}"#
+        );
+    }
+
+    #[test]
+    fn test_merge_edits_the_last_duplicate() {
+        let settings = r#"{"statusLine": {"command": "a"}, "statusLine": {"command": "b"}}"#;
+        let merged = merge_status_line(settings, "ccline").unwrap();
+        assert!(merged.starts_with(r#"{"statusLine": {"command": "a"}, "statusLine": {"type""#));
+        assert_eq!(status_line_command(&merged).as_deref(), Some("ccline"));
+    }
+
+    #[test]
+    fn test_install_and_uninstall_settings() {
+        let dir = std::env::temp_dir().join(format!("ccline_install_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let settings_path = dir.join("settings.json");
+        let binary = dir.join("ccline");
+        let program = binary.to_string_lossy().into_owned();
+        fs::write(&settings_path, SETTINGS).unwrap();
+        let command = || status_line_command(&fs::read_to_string(&settings_path).unwrap());
+
+        install_settings(&settings_path, &program, &binary).unwrap();
+        assert_eq!(command(), Some(program.clone()));
+        assert_eq!(
+            fs::read_to_string(backup_path(&settings_path)).unwrap(),
+            SETTINGS
+        );
+
+        // A reinstall keeps the flags added since
+        let with_flags = format!("{} --segments model,git", program);
+        let current = fs::read_to_string(&settings_path).unwrap();
+        fs::write(
+            &settings_path,
+            merge_status_line(&current, &with_flags).unwrap(),
+        )
+        .unwrap();
+        install_settings(&settings_path, &program, &binary).unwrap();
+        assert_eq!(command(), Some(with_flags));
+
+        assert!(restore_settings(&settings_path, &binary).unwrap());
+        let restored = fs::read_to_string(&settings_path).unwrap();
+        let backup_left = backup_path(&settings_path).exists();
+
+        // Someone else's statusline stays as it is
+        let foreign = !restore_settings(&settings_path, &dir.join("other")).unwrap();
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(restored, SETTINGS);
+        assert!(!backup_left);
+        assert!(foreign);
+    }
+
+    #[test]
+    fn test_merge_into_empty_settings() {
+        let merged = merge_status_line("", "ccline").unwrap();
+        let value: Value = serde_json::from_str(&merged).unwrap();
+        assert_eq!(value["statusLine"]["command"], "ccline");
+        assert!(merge_status_line("[]", "ccline").is_err());
+    }
+
+    #[test]
+    fn test_runs_binary_matches_the_program() {
+        let home = dirs::home_dir().unwrap();
+        let binary = home.join(".claude").join("ccline").join("ccline");
+        let settings = |command: &str| {
+            serde_json::json!({ "statusLine": { "type": "command", "command": command } })
+                .to_string()
+        };
+
+        assert!(runs_binary(&settings(&binary.to_string_lossy()), &binary));
+        assert!(runs_binary(&settings("~/.claude/ccline/ccline"), &binary));
+        assert!(runs_binary(
+            &settings("$HOME/.claude/ccline/ccline --segments model,git --set theme=plain"),
+            &binary
+        ));
+        assert!(!runs_binary(&settings("~/bin/old-statusline"), &binary));
+        assert!(!runs_binary("{}", &binary));
+    }
+}
@@ -0,0 +1,6 @@
+pub mod completions;
+pub mod doctor;
+pub mod install;
+pub mod preview;
+pub mod replay;
+pub mod report;
@@ -0,0 +1,172 @@
+use crate::cli::PreviewArgs;
+use crate::config::{Config, InputData};
+use crate::core::theme::THEMES;
+use crate::core::StatusLineGenerator;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A short session: a prompt, then an edit that used about 71k tokens of context.
+const SAMPLE_TRANSCRIPT: &str = r#"{"type":"user","timestamp":"2025-08-12T09:30:00.000Z","message":{"role":"user","content":"Add a preview subcommand"}}
+{"type":"assistant","timestamp":"2025-08-12T09:30:12.000Z","requestId":"req_preview_1","message":{"id":"msg_preview_1","type":"message","role":"assistant","model":"claude-sonnet-4-20250514","content":[{"type":"tool_use","name":"Edit","input":{"file_path":"src/main.rs","old_string":"a\nb","new_string":"a\nc\nd"}}],"usage":{"input_tokens":12,"cache_creation_input_tokens":9000,"cache_read_input_tokens":62000,"output_tokens":850}}}"#;
+
+pub fn run(args: &PreviewArgs, config: Config) -> io::Result<()> {
+    // Kept alive until rendering is done; its transcript is removed on drop
+    let mut sample = None;
+    let raw_input = match &args.input {
+        Some(path) => fs::read_to_string(path)?,
+        None => sample.insert(SampleSession::create()?).payload(),
+    };
+    let (input, error) = InputData::parse_lenient(&raw_input);
+    if let Some(error) = &error {
+        eprintln!("Payload could not be fully read: {}", error);
+    }
+
+    let themes: Vec<String> = if args.all_themes {
+        THEMES.iter().map(|theme| theme.name.to_string()).collect()
+    } else {
+        vec![config.theme.clone()]
+    };
+
+    for (i, theme) in themes.iter().enumerate() {
+        let generator = StatusLineGenerator::new(Config {
+            theme: theme.clone(),
+            ..config.clone()
+        });
+        let mut statusline = generator.generate(&input);
+        if error.is_some() {
+            generator.mark_input_error(&mut statusline);
+        }
+
+        if i > 0 {
+            println!();
+        }
+        if args.all_themes {
+            println!("{}", theme);
+        }
+        println!("{}", statusline);
+        for &width in &args.width {
+            println!("{:>4} │{}", width, truncate_visible(&statusline, width));
+        }
+    }
+
+    Ok(())
+}
+
+/// The built-in sample session, with its transcript in a temporary file.
+pub struct SampleSession {
+    transcript: PathBuf,
+}
+
+impl SampleSession {
+    pub fn create() -> io::Result<Self> {
+        let transcript =
+            env::temp_dir().join(format!("ccline-preview-{}.jsonl", std::process::id()));
+        fs::write(&transcript, SAMPLE_TRANSCRIPT)?;
+        Ok(Self { transcript })
+    }
+
+    /// The stdin payload Claude Code would send for this session, run from the current directory.
+    pub fn payload(&self) -> String {
+        sample_payload(&self.transcript)
+    }
+}
+
+impl Drop for SampleSession {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.transcript);
+    }
+}
+
+fn sample_payload(transcript: &Path) -> String {
+    let current_dir = env::current_dir()
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    serde_json::json!({
+        "session_id": "preview",
+        "transcript_path": transcript,
+        "cwd": current_dir,
+        "model": { "id": "claude-sonnet-4-20250514", "display_name": "Sonnet 4" },
+        "workspace": { "current_dir": current_dir, "project_dir": current_dir },
+        "version": "1.0.80",
+        "output_style": { "name": "default" },
+        "cost": {
+            "total_cost_usd": 0.42,
+            "total_duration_ms": 2_700_000,
+            "total_api_duration_ms": 480_000,
+            "total_lines_added": 120,
+            "total_lines_removed": 45
+        }
+    })
+    .to_string()
+}
+
+/// Cut `line` to `width` visible characters, ending with `…` when something was cut.
+///
+/// Escape sequences take no room and are kept, so colors stay intact up to the cut.
+fn truncate_visible(line: &str, width: usize) -> String {
+    let visible = strip_escapes(line).chars().count();
+    if visible <= width {
+        return line.to_string();
+    }
+
+    let mut out = String::new();
+    let mut shown = 0;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            out.push(c);
+            // Copy the whole `ESC [ ... m` sequence
+            for c in chars.by_ref() {
+                out.push(c);
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+            continue;
+        }
+        if shown + 1 >= width {
+            break;
+        }
+        out.push(c);
+        shown += 1;
+    }
+    if width > 0 {
+        out.push('…');
+    }
+    if line.contains('\x1b') {
+        out.push_str("\x1b[0m");
+    }
+    out
+}
+
+fn strip_escapes(line: &str) -> String {
+    let mut out = String::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_visible() {
+        let line = "\x1b[1;36mSonnet 4\x1b[0m\x1b[37m | \x1b[0m\x1b[1;32mcrate\x1b[0m";
+        assert_eq!(truncate_visible(line, 40), line);
+        assert_eq!(strip_escapes(&truncate_visible(line, 10)), "Sonnet 4 …");
+        assert_eq!(truncate_visible(line, 3), "\x1b[1;36mSo…\x1b[0m");
+    }
+}
@@ -0,0 +1,36 @@
+use crate::cli::ReplayArgs;
+use crate::config::Config;
+use crate::core::capture::{self, Capture};
+use crate::core::StatusLineGenerator;
+use std::io;
+
+pub fn run(args: &ReplayArgs, config: Config) -> io::Result<()> {
+    let captures = capture::find_captures(&args.dir)?;
+    if captures.is_empty() {
+        println!("No captures found in {}", args.dir.display());
+        return Ok(());
+    }
+
+    for path in captures {
+        let capture = match Capture::load(&path) {
+            Ok(capture) => capture,
+            Err(e) => {
+                eprintln!("Skipping {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        let (input, error) = capture.input();
+
+        let generator =
+            StatusLineGenerator::new(config.clone()).with_git_snapshot(capture.git.clone());
+        let mut statusline = generator.generate(&input);
+        if error.is_some() {
+            generator.mark_input_error(&mut statusline);
+        }
+
+        let name = path.file_name().unwrap_or(path.as_os_str());
+        println!("{}", name.to_string_lossy());
+        println!("{}", statusline);
+    }
+    Ok(())
+}
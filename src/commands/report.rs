@@ -0,0 +1,270 @@
+use crate::cli::{GroupBy, ReportArgs, ReportFormat};
+use crate::core::aggregate::{Period, UsageTotals};
+use crate::core::history::{self, UsageRecord};
+use crate::core::segments::cost::format_cost;
+use chrono::{Local, NaiveDate, TimeZone, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+#[derive(Debug, Serialize)]
+pub struct ReportRow {
+    pub key: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+#[derive(Debug, Serialize)]
+struct Report<'a> {
+    group_by: &'a str,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    rows: &'a [ReportRow],
+    total: &'a UsageTotals,
+}
+
+pub fn run(args: &ReportArgs) -> io::Result<()> {
+    // Transcripts untouched since the start of `since` can't contain anything in range
+    let load_since = args
+        .since
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .and_then(|start| Local.from_local_datetime(&start).earliest())
+        .map(|start| start.with_timezone(&Utc));
+
+    let records: Vec<UsageRecord> = history::load_usage_records(load_since)
+        .into_iter()
+        .filter(|record| {
+            let date = record.timestamp.with_timezone(&Local).date_naive();
+            args.since.is_none_or(|since| date >= since)
+                && args.until.is_none_or(|until| date <= until)
+        })
+        .collect();
+
+    let rows = group_records(&records, args.by);
+    let mut total = UsageTotals::default();
+    for row in &rows {
+        total.add(&row.totals);
+    }
+
+    let mut stdout = io::stdout().lock();
+    match args.format {
+        ReportFormat::Table => write_table(&mut stdout, args.by, &rows, &total),
+        ReportFormat::Csv => write_csv(&mut stdout, args.by, &rows),
+        ReportFormat::Json => {
+            let report = Report {
+                group_by: group_label(args.by),
+                since: args.since,
+                until: args.until,
+                rows: &rows,
+                total: &total,
+            };
+            serde_json::to_writer_pretty(&mut stdout, &report)?;
+            writeln!(stdout)
+        }
+    }
+}
+
+/// Sum records per group, ordered by key.
+pub fn group_records(records: &[UsageRecord], by: GroupBy) -> Vec<ReportRow> {
+    let mut groups: BTreeMap<String, UsageTotals> = BTreeMap::new();
+    for record in records {
+        groups
+            .entry(group_key(record, by))
+            .or_default()
+            .add_record(record);
+    }
+
+    groups
+        .into_iter()
+        .map(|(key, totals)| ReportRow { key, totals })
+        .collect()
+}
+
+fn group_key(record: &UsageRecord, by: GroupBy) -> String {
+    let date = record.timestamp.with_timezone(&Local).date_naive();
+    match by {
+        GroupBy::Day => date.format("%Y-%m-%d").to_string(),
+        // Weeks are keyed by their Monday
+        GroupBy::Week => Period::Week.range(date).0.format("%Y-%m-%d").to_string(),
+        GroupBy::Month => date.format("%Y-%m").to_string(),
+        GroupBy::Project => record.project.clone(),
+        GroupBy::Model => record.model.clone(),
+        GroupBy::Session => record.session_id.clone(),
+    }
+}
+
+fn group_label(by: GroupBy) -> &'static str {
+    match by {
+        GroupBy::Day => "day",
+        GroupBy::Week => "week",
+        GroupBy::Month => "month",
+        GroupBy::Project => "project",
+        GroupBy::Model => "model",
+        GroupBy::Session => "session",
+    }
+}
+
+fn write_table<W: Write>(
+    out: &mut W,
+    by: GroupBy,
+    rows: &[ReportRow],
+    total: &UsageTotals,
+) -> io::Result<()> {
+    if rows.is_empty() {
+        return writeln!(out, "No usage found.");
+    }
+
+    let label = group_label(by);
+    let header = [
+        label[..1].to_uppercase() + &label[1..],
+        "Input".to_string(),
+        "Output".to_string(),
+        "Cache Write".to_string(),
+        "Cache Read".to_string(),
+        "Total Tokens".to_string(),
+        "Cost".to_string(),
+    ];
+    let cells = |key: &str, totals: &UsageTotals| {
+        [
+            key.to_string(),
+            group_digits(totals.input_tokens),
+            group_digits(totals.output_tokens),
+            group_digits(totals.cache_creation_tokens),
+            group_digits(totals.cache_read_tokens),
+            group_digits(totals.total_tokens()),
+            format_cost(totals.cost),
+        ]
+    };
+
+    let body: Vec<[String; 7]> = rows
+        .iter()
+        .map(|row| cells(&row.key, &row.totals))
+        .collect();
+    let footer = cells("Total", total);
+
+    let mut widths = header.clone().map(|cell| cell.chars().count());
+    for line in body.iter().chain([&footer]) {
+        for (width, cell) in widths.iter_mut().zip(line) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let write_line = |out: &mut W, line: &[String; 7]| -> io::Result<()> {
+        let padded: Vec<String> = line
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(column, (cell, &width))| {
+                // Group names read left to right, numbers line up on the right
+                if column == 0 {
+                    format!("{:<width$}", cell)
+                } else {
+                    format!("{:>width$}", cell)
+                }
+            })
+            .collect();
+        writeln!(out, "{}", padded.join("  ").trim_end())
+    };
+    let rule = widths
+        .iter()
+        .map(|&width| "─".repeat(width))
+        .collect::<Vec<_>>()
+        .join("  ");
+
+    write_line(out, &header)?;
+    writeln!(out, "{}", rule)?;
+    for line in &body {
+        write_line(out, line)?;
+    }
+    writeln!(out, "{}", rule)?;
+    write_line(out, &footer)
+}
+
+fn write_csv<W: Write>(out: &mut W, by: GroupBy, rows: &[ReportRow]) -> io::Result<()> {
+    writeln!(
+        out,
+        "{},input_tokens,output_tokens,cache_creation_tokens,cache_read_tokens,total_tokens,cost_usd",
+        group_label(by)
+    )?;
+    for row in rows {
+        let totals = &row.totals;
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{:.6}",
+            csv_field(&row.key),
+            totals.input_tokens,
+            totals.output_tokens,
+            totals.cache_creation_tokens,
+            totals.cache_read_tokens,
+            totals.total_tokens(),
+            totals.cost
+        )?;
+    }
+    Ok(())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// `1234567` → `1,234,567`
+fn group_digits(value: u64) -> String {
+    let digits = value.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::transcript::parse_timestamp;
+
+    fn record(timestamp: &str, model: &str, cost: f64) -> UsageRecord {
+        UsageRecord {
+            timestamp: parse_timestamp(timestamp).unwrap(),
+            project: "/work/crate".to_string(),
+            session_id: "session".to_string(),
+            model: model.to_string(),
+            input_tokens: 1000,
+            output_tokens: 200,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 5000,
+            cost,
+        }
+    }
+
+    #[test]
+    fn test_group_by_model() {
+        let records = vec![
+            record("2025-08-12T10:00:00Z", "claude-sonnet-4", 0.5),
+            record("2025-08-12T11:00:00Z", "claude-3-5-haiku", 0.1),
+            record("2025-08-13T10:00:00Z", "claude-sonnet-4", 0.25),
+        ];
+
+        let rows = group_records(&records, GroupBy::Model);
+        let keys: Vec<&str> = rows.iter().map(|row| row.key.as_str()).collect();
+        assert_eq!(keys, ["claude-3-5-haiku", "claude-sonnet-4"]);
+        assert_eq!(rows[1].totals.messages, 2);
+        assert_eq!(rows[1].totals.total_tokens(), 12400);
+        assert!((rows[1].totals.cost - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_formatting_helpers() {
+        assert_eq!(group_digits(0), "0");
+        assert_eq!(group_digits(999), "999");
+        assert_eq!(group_digits(1234567), "1,234,567");
+        assert_eq!(csv_field("/work/crate"), "/work/crate");
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+}
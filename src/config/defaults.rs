@@ -1,14 +1,81 @@
-use super::types::{Config, SegmentsConfig};
+use super::types::{
+    BlockConfig, BudgetConfig, Config, CostConfig, DirectoryConfig, DirectoryStyle, GitConfig,
+    GitSymbols, LinesConfig, ModelConfig, OutputStyleConfig, SegmentsConfig, SessionConfig,
+    SpendConfig, TimeConfig, UsageConfig, UsageDisplay, VersionConfig,
+};
 
 pub const DEFAULT_CONFIG: Config = Config {
     theme: String::new(), // Set to "dark" at runtime
+    show_input_errors: true,
     segments: SegmentsConfig {
-        directory: true,
-        git: true,
-        model: true,
-        time: false,
-        usage: true,
-        cost: true,
+        directory: DirectoryConfig {
+            enabled: true,
+            style: DirectoryStyle::Basename,
+            components: 2,
+            max_length: None,
+            show_project: true,
+        },
+        git: GitConfig {
+            enabled: true,
+            show_sha: false,
+            show_ahead_behind: true,
+            max_branch_length: None,
+            symbols: GitSymbols {
+                branch: None,
+                clean: None,
+                dirty: None,
+                conflicts: None,
+                ahead: None,
+                behind: None,
+            },
+        },
+        lines: LinesConfig { enabled: true },
+        model: ModelConfig {
+            enabled: true,
+            format: None,
+            aliases: Vec::new(),
+        },
+        time: TimeConfig {
+            enabled: false,
+            format: None,
+            hour12: false,
+            seconds: false,
+            timezone: None,
+            label: None,
+            clocks: Vec::new(),
+        },
+        session: SessionConfig { enabled: false },
+        usage: UsageConfig {
+            enabled: true,
+            display: UsageDisplay::Text,
+            bar_width: 10,
+            warning_threshold: 0.6,
+            critical_threshold: 0.8,
+            auto_compact_threshold: 0.92,
+        },
+        cost: CostConfig {
+            enabled: true,
+            show_subagents: false,
+            precision: None,
+        },
+        block: BlockConfig { enabled: false },
+        spend: SpendConfig {
+            enabled: false,
+            today: true,
+            week: true,
+            month: true,
+        },
+        output_style: OutputStyleConfig { enabled: false },
+        version: VersionConfig {
+            enabled: false,
+            pinned: None,
+        },
+    },
+    budget: BudgetConfig {
+        session: None,
+        daily: None,
+        monthly: None,
+        warning_threshold: 0.5,
     },
 };
 
@@ -16,14 +83,93 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             theme: "dark".to_string(),
-            segments: SegmentsConfig {
-                directory: true,
-                git: true,
-                model: true,
-                time: false,
-                usage: true,
-                cost: true,
-            },
+            show_input_errors: DEFAULT_CONFIG.show_input_errors,
+            segments: SegmentsConfig::default(),
+            budget: BudgetConfig::default(),
         }
     }
 }
+
+impl Default for SegmentsConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG.segments
+    }
+}
+
+impl Default for ModelConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG.segments.model
+    }
+}
+
+impl Default for DirectoryConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG.segments.directory
+    }
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG.segments.git
+    }
+}
+
+impl Default for TimeConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG.segments.time
+    }
+}
+
+impl Default for UsageConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG.segments.usage
+    }
+}
+
+impl Default for CostConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG.segments.cost
+    }
+}
+
+impl Default for SpendConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG.segments.spend
+    }
+}
+
+impl Default for LinesConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG.segments.lines
+    }
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG.segments.session
+    }
+}
+
+impl Default for BlockConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG.segments.block
+    }
+}
+
+impl Default for OutputStyleConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG.segments.output_style
+    }
+}
+
+impl Default for VersionConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG.segments.version
+    }
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG.budget
+    }
+}
@@ -0,0 +1,149 @@
+/// Every scalar config key, as a dotted path into `config.toml`, with a short description.
+///
+/// Shown in `--help` and the man page; segment toggles like `segments.time` also accept
+/// `segments.time.enabled`.
+pub const CONFIG_KEYS: &[(&str, &str)] = &[
+    ("theme", "dark, light or plain"),
+    (
+        "show_input_errors",
+        "mark a statusline rendered from an unreadable payload",
+    ),
+    ("segments.model.enabled", "model name"),
+    (
+        "segments.model.format",
+        "template, e.g. {tier} {version} {context}",
+    ),
+    ("segments.directory.enabled", "current directory"),
+    (
+        "segments.directory.style",
+        "basename, full, fish, repo or last",
+    ),
+    (
+        "segments.directory.components",
+        "components kept by the last style",
+    ),
+    ("segments.directory.max_length", "cut longer paths with …"),
+    (
+        "segments.directory.show_project",
+        "project › sub/path in a project subdirectory",
+    ),
+    ("segments.git.enabled", "branch, status and ahead/behind"),
+    ("segments.git.show_sha", "short commit hash"),
+    (
+        "segments.git.show_ahead_behind",
+        "commits ahead of/behind upstream",
+    ),
+    (
+        "segments.git.max_branch_length",
+        "cut longer branch names with …",
+    ),
+    ("segments.git.symbols.branch", "branch icon"),
+    ("segments.git.symbols.clean", "clean working tree"),
+    ("segments.git.symbols.dirty", "uncommitted changes"),
+    ("segments.git.symbols.conflicts", "merge conflicts"),
+    ("segments.git.symbols.ahead", "prefix of the ahead count"),
+    ("segments.git.symbols.behind", "prefix of the behind count"),
+    ("segments.lines.enabled", "lines added and removed"),
+    ("segments.time.enabled", "clock"),
+    ("segments.time.format", "strftime format, e.g. %H:%M"),
+    ("segments.time.hour12", "12-hour clock"),
+    ("segments.time.seconds", "show seconds"),
+    ("segments.time.timezone", "IANA timezone, e.g. Asia/Tokyo"),
+    ("segments.time.label", "text before the time"),
+    ("segments.session.enabled", "session duration"),
+    ("segments.usage.enabled", "context window usage"),
+    ("segments.usage.display", "text, bar or both"),
+    ("segments.usage.bar_width", "progress bar cells"),
+    (
+        "segments.usage.warning_threshold",
+        "fraction of the context shown in yellow",
+    ),
+    (
+        "segments.usage.critical_threshold",
+        "fraction of the context shown in red",
+    ),
+    (
+        "segments.usage.auto_compact_threshold",
+        "fraction where Claude Code compacts",
+    ),
+    ("segments.cost.enabled", "session cost"),
+    (
+        "segments.cost.show_subagents",
+        "show the sub-agent share of the cost",
+    ),
+    (
+        "segments.cost.precision",
+        "decimal places, adaptive by default",
+    ),
+    ("segments.block.enabled", "current 5-hour usage block"),
+    ("segments.spend.enabled", "spend across all projects"),
+    ("segments.spend.today", "include today"),
+    ("segments.spend.week", "include this week"),
+    ("segments.spend.month", "include this month"),
+    ("segments.output_style.enabled", "active output style"),
+    ("segments.version.enabled", "Claude Code version"),
+    (
+        "segments.version.pinned",
+        "team version; older ones show an update",
+    ),
+    ("budget.session", "session budget in USD"),
+    ("budget.daily", "daily budget in USD"),
+    ("budget.monthly", "monthly budget in USD"),
+    (
+        "budget.warning_threshold",
+        "fraction of a budget that turns the cost orange",
+    ),
+];
+
+/// `CONFIG_KEYS` as a help section.
+pub fn keys_help() -> String {
+    let width = CONFIG_KEYS
+        .iter()
+        .map(|(key, _)| key.len())
+        .max()
+        .unwrap_or(0);
+    let mut help = String::from("Config keys (set in config.toml or with --set KEY=VALUE):\n");
+    for (key, description) in CONFIG_KEYS {
+        help.push_str(&format!("  {:<width$}  {}\n", key, description));
+    }
+    help
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn leaf_keys(prefix: &str, value: &toml::Value, keys: &mut Vec<String>) {
+        match value {
+            toml::Value::Table(table) => {
+                for (name, value) in table {
+                    let path = if prefix.is_empty() {
+                        name.clone()
+                    } else {
+                        format!("{}.{}", prefix, name)
+                    };
+                    leaf_keys(&path, value, keys);
+                }
+            }
+            // Lists like `segments.time.clocks` only make sense in the file
+            toml::Value::Array(_) => {}
+            _ => keys.push(prefix.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_every_default_key_is_documented() {
+        let defaults = toml::Value::try_from(Config::default()).unwrap();
+        let mut keys = Vec::new();
+        leaf_keys("", &defaults, &mut keys);
+
+        for key in keys {
+            assert!(
+                CONFIG_KEYS.iter().any(|(documented, _)| *documented == key),
+                "{} is missing from CONFIG_KEYS",
+                key
+            );
+        }
+    }
+}
@@ -1,16 +1,45 @@
 use super::types::Config;
-use std::path::Path;
+use crate::core::{log, trace};
+use std::fs;
+use std::path::{Path, PathBuf};
 
 pub struct ConfigLoader;
 
 impl ConfigLoader {
     pub fn load() -> Config {
-        // Return default config for now, implement multi-layer loading later
-        Config::default()
+        // Fall back to defaults when there is no user config or it can't be read
+        let Some(path) = Self::default_path().filter(|path| path.exists()) else {
+            trace::event("config", || "no config file, using defaults".to_string());
+            return Config::default();
+        };
+        Self::load_from_path(&path).unwrap_or_else(|e| {
+            log::append(&format!(
+                "could not load config {}, using defaults: {}",
+                path.display(),
+                e
+            ));
+            Config::default()
+        })
     }
 
-    pub fn load_from_path<P: AsRef<Path>>(_path: P) -> Result<Config, Box<dyn std::error::Error>> {
-        // Load config from file
-        Ok(Config::default())
+    pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<Config, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let result = fs::read_to_string(path)
+            .map_err(Into::into)
+            .and_then(|content| toml::from_str(&content).map_err(Into::into));
+        trace::event("config", || match &result {
+            Ok(_) => format!("defaults, then {}", path.display()),
+            Err(e) => format!(
+                "{} could not be loaded, using defaults: {}",
+                path.display(),
+                e
+            ),
+        });
+        result
+    }
+
+    /// `~/.claude/ccline/config.toml`
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".claude").join("ccline").join("config.toml"))
     }
 }
@@ -1,5 +1,7 @@
 pub mod defaults;
+pub mod keys;
 pub mod loader;
+pub mod overrides;
 pub mod types;
 
 pub use defaults::DEFAULT_CONFIG;
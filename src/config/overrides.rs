@@ -0,0 +1,170 @@
+use super::keys::CONFIG_KEYS;
+use super::types::Config;
+use toml::Value;
+
+/// Segments `--segments` can turn on, in statusline order.
+pub const SEGMENT_NAMES: &[&str] = &[
+    "model",
+    "output_style",
+    "directory",
+    "git",
+    "lines",
+    "time",
+    "session",
+    "usage",
+    "cost",
+    "spend",
+    "block",
+    "version",
+];
+
+/// Apply `key.path=value` on top of `config`.
+///
+/// The value is read as TOML (`true`, `0.8`, `"text"`) and falls back to a plain string,
+/// so `--set segments.usage.display=bar` works without quotes. On error `config` is unchanged.
+pub fn apply_override(config: &mut Config, assignment: &str) -> Result<(), String> {
+    let (key, raw_value) = assignment
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got `{}`", assignment))?;
+    let key = key.trim();
+    if !is_known_key(key) {
+        return Err(format!(
+            "unknown config key `{}` (see `ccline --help` for the list)",
+            key
+        ));
+    }
+
+    let value = parse_value(raw_value.trim());
+    let mut root = Value::try_from(&*config).map_err(|e| e.to_string())?;
+    set_path(&mut root, key, value);
+    *config = root.try_into().map_err(|e: toml::de::Error| {
+        format!("{}: {}", key, e.message().trim().replace('\n', " "))
+    })?;
+    Ok(())
+}
+
+/// Show exactly the listed segments.
+pub fn apply_segments(config: &mut Config, names: &[String]) -> Result<(), String> {
+    if let Some(unknown) = names
+        .iter()
+        .find(|name| !SEGMENT_NAMES.contains(&name.trim()))
+    {
+        return Err(format!(
+            "unknown segment `{}`, expected one of {}",
+            unknown,
+            SEGMENT_NAMES.join(", ")
+        ));
+    }
+
+    for segment in SEGMENT_NAMES {
+        let enabled = names.iter().any(|name| name.trim() == *segment);
+        apply_override(config, &format!("segments.{}={}", segment, enabled))?;
+    }
+    Ok(())
+}
+
+/// A documented key, or a segment whose toggle is documented as `<segment>.enabled`.
+fn is_known_key(key: &str) -> bool {
+    CONFIG_KEYS
+        .iter()
+        .any(|(known, _)| *known == key || known.strip_suffix(".enabled") == Some(key))
+}
+
+fn parse_value(raw: &str) -> Value {
+    toml::from_str::<toml::Table>(&format!("value = {}", raw))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+/// Set a dotted path, creating tables on the way.
+///
+/// Segments are written either as `git = true` or as a `[segments.git]` table; a bool
+/// on the way becomes `{ enabled = <bool> }` and a bool set on a segment table sets its
+/// `enabled`. Any other value, or a key that isn't there yet, is set as given.
+fn set_path(root: &mut Value, key: &str, value: Value) {
+    let parts: Vec<&str> = key.split('.').collect();
+    let is_segment = parts.len() == 2 && parts[0] == "segments";
+    let Some((last, path)) = parts.split_last() else {
+        return;
+    };
+
+    let mut current = root;
+    for part in path {
+        if let Value::Boolean(enabled) = current {
+            let mut table = toml::Table::new();
+            table.insert("enabled".to_string(), Value::Boolean(*enabled));
+            *current = Value::Table(table);
+        }
+        let Value::Table(table) = current else {
+            return;
+        };
+        current = table
+            .entry(part.to_string())
+            .or_insert_with(|| Value::Table(toml::Table::new()));
+    }
+
+    let Value::Table(parent) = current else {
+        return;
+    };
+    match (parent.get_mut(*last), value) {
+        (Some(Value::Table(segment)), Value::Boolean(enabled)) if is_segment => {
+            segment.insert("enabled".to_string(), Value::Boolean(enabled));
+        }
+        (_, value) => {
+            parent.insert(last.to_string(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::UsageDisplay;
+
+    #[test]
+    fn test_apply_override() {
+        let mut config = Config::default();
+        apply_override(&mut config, "segments.usage.display=bar").unwrap();
+        apply_override(&mut config, "segments.usage.bar_width = 16").unwrap();
+        apply_override(&mut config, "budget.daily=20").unwrap();
+        apply_override(&mut config, "segments.time=true").unwrap();
+        apply_override(&mut config, "segments.time.format=%H:%M:%S").unwrap();
+
+        assert_eq!(config.segments.usage.display, UsageDisplay::Bar);
+        assert_eq!(config.segments.usage.bar_width, 16);
+        assert_eq!(config.budget.daily, Some(20.0));
+        assert!(config.segments.time.enabled);
+        assert_eq!(config.segments.time.format.as_deref(), Some("%H:%M:%S"));
+
+        assert!(apply_override(&mut config, "segments.nope=true").is_err());
+        assert!(apply_override(&mut config, "segments.usage.bar_width=wide").is_err());
+        assert_eq!(config.segments.usage.bar_width, 16);
+    }
+
+    #[test]
+    fn test_override_key_missing_from_defaults() {
+        // `None` options aren't serialized, so these keys start out absent
+        let mut config = Config::default();
+        apply_override(&mut config, "segments.time.label=true").unwrap_err();
+        apply_override(&mut config, "segments.time.label=UTC").unwrap();
+        apply_override(&mut config, "budget.daily=20.5").unwrap();
+
+        assert_eq!(config.segments.time.label.as_deref(), Some("UTC"));
+        assert_eq!(config.budget.daily, Some(20.5));
+
+        let error = apply_override(&mut config, "segments.time.timezone=true").unwrap_err();
+        assert!(error.contains("boolean"), "{}", error);
+    }
+
+    #[test]
+    fn test_apply_segments() {
+        let mut config = Config::default();
+        let names = ["model".to_string(), "time".to_string()];
+        apply_segments(&mut config, &names).unwrap();
+
+        assert!(config.segments.model.enabled && config.segments.time.enabled);
+        assert!(!config.segments.git.enabled && !config.segments.cost.enabled);
+        assert!(apply_segments(&mut config, &["clock".to_string()]).is_err());
+    }
+}
@@ -1,37 +1,421 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct Config {
     pub theme: String,
+    /// Append a `⚠` marker when the stdin payload from Claude Code can't be fully read
+    pub show_input_errors: bool,
     pub segments: SegmentsConfig,
+    pub budget: BudgetConfig,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct SegmentsConfig {
-    pub directory: bool,
-    pub git: bool,
-    pub model: bool,
-    pub time: bool,
-    pub usage: bool,
-    pub cost: bool,
+    #[serde(deserialize_with = "bool_or_options")]
+    pub directory: DirectoryConfig,
+    #[serde(deserialize_with = "bool_or_options")]
+    pub git: GitConfig,
+    #[serde(deserialize_with = "bool_or_options")]
+    pub lines: LinesConfig,
+    #[serde(deserialize_with = "bool_or_options")]
+    pub model: ModelConfig,
+    #[serde(deserialize_with = "bool_or_options")]
+    pub time: TimeConfig,
+    #[serde(deserialize_with = "bool_or_options")]
+    pub session: SessionConfig,
+    #[serde(deserialize_with = "bool_or_options")]
+    pub usage: UsageConfig,
+    #[serde(deserialize_with = "bool_or_options")]
+    pub cost: CostConfig,
+    #[serde(deserialize_with = "bool_or_options")]
+    pub block: BlockConfig,
+    #[serde(deserialize_with = "bool_or_options")]
+    pub spend: SpendConfig,
+    #[serde(deserialize_with = "bool_or_options")]
+    pub output_style: OutputStyleConfig,
+    #[serde(deserialize_with = "bool_or_options")]
+    pub version: VersionConfig,
+}
+
+/// Spending limits in USD; the cost segment changes color as spend approaches them
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BudgetConfig {
+    pub session: Option<f64>,
+    pub daily: Option<f64>,
+    pub monthly: Option<f64>,
+    /// Fraction of a budget at which the warning color kicks in
+    pub warning_threshold: f64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ModelConfig {
+    pub enabled: bool,
+    /// Template with `{family}`, `{tier}`, `{version}`, `{date}`, `{context}`, `{id}` and
+    /// `{display_name}`; `{tier} {version} {context}` when unset
+    pub format: Option<String>,
+    /// Checked in order before `format`; the first matching rule names the model
+    pub aliases: Vec<ModelAlias>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ModelAlias {
+    /// Model id to match, case-insensitive, with `*` as a wildcard
+    pub pattern: String,
+    /// Same placeholders as `format`
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DirectoryConfig {
+    pub enabled: bool,
+    pub style: DirectoryStyle,
+    /// How many trailing components the `last` style keeps
+    pub components: usize,
+    /// Longer paths are cut from the start with `…`; no limit when unset
+    pub max_length: Option<usize>,
+    /// Show `project › sub/path` when Claude is in a subdirectory of the project; `fish` and
+    /// `last` shorten the sub path, the other styles show all of it
+    pub show_project: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DirectoryStyle {
+    /// `crate`
+    Basename,
+    /// `~/work/projects/crate`
+    Full,
+    /// `~/w/p/crate`
+    Fish,
+    /// `crate/src/core`, relative to the git repository root
+    Repo,
+    /// `projects/crate`, the last `components` components
+    Last,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GitConfig {
+    pub enabled: bool,
+    /// Append the short commit hash
+    pub show_sha: bool,
+    /// Show commits ahead of and behind the upstream branch
+    pub show_ahead_behind: bool,
+    /// Longer branch names are cut with `…`; no limit when unset
+    pub max_branch_length: Option<usize>,
+    pub symbols: GitSymbols,
+}
+
+/// Replacements for the git segment's icons; unset ones keep the default
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GitSymbols {
+    pub branch: Option<String>,
+    pub clean: Option<String>,
+    pub dirty: Option<String>,
+    pub conflicts: Option<String>,
+    pub ahead: Option<String>,
+    pub behind: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TimeConfig {
+    pub enabled: bool,
+    /// strftime-style format, e.g. `%a %H:%M`; overrides `hour12` and `seconds`
+    pub format: Option<String>,
+    pub hour12: bool,
+    pub seconds: bool,
+    /// IANA timezone name such as `Asia/Tokyo`; local time when unset
+    pub timezone: Option<String>,
+    pub label: Option<String>,
+    /// Additional clocks rendered as their own segments
+    pub clocks: Vec<ClockConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ClockConfig {
+    pub timezone: String,
+    pub label: Option<String>,
+    pub format: Option<String>,
+}
+
+/// Context window usage
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UsageConfig {
+    pub enabled: bool,
+    pub display: UsageDisplay,
+    /// Width of the progress bar in characters
+    pub bar_width: usize,
+    /// Fractions of the context window at which the segment turns yellow and red
+    pub warning_threshold: f64,
+    pub critical_threshold: f64,
+    /// Fraction of the context window at which Claude Code auto-compacts
+    pub auto_compact_threshold: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageDisplay {
+    /// `45% · 90k tokens`
+    Text,
+    /// `████▌░░░░░ 45%`
+    Bar,
+    /// `████▌░░░░░ 45% · 90k tokens`
+    Both,
+}
+
+/// Session cost
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CostConfig {
+    pub enabled: bool,
+    /// Show how much of the session cost came from sub-agents
+    pub show_subagents: bool,
+    /// Decimal places; by default more are shown for smaller amounts
+    pub precision: Option<usize>,
+}
+
+/// Spend across all sessions, per local calendar period
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SpendConfig {
+    pub enabled: bool,
+    pub today: bool,
+    /// Monday through today
+    pub week: bool,
+    /// Month to date
+    pub month: bool,
+}
+
+/// Lines added and removed in the session
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LinesConfig {
+    pub enabled: bool,
+}
+
+/// Session duration, API time and idle time
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    pub enabled: bool,
+}
+
+/// Current 5-hour usage block
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BlockConfig {
+    pub enabled: bool,
+}
+
+/// Output style Claude is running with
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct OutputStyleConfig {
+    pub enabled: bool,
+}
+
+/// Claude Code version
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct VersionConfig {
+    pub enabled: bool,
+    /// The version the team is on; an older running version shows that an update is available
+    pub pinned: Option<String>,
+}
+
+/// Options tables for a segment that can also be switched with a plain bool.
+pub trait SegmentToggle: Default {
+    fn set_enabled(&mut self, enabled: bool);
+}
+
+impl SegmentToggle for ModelConfig {
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+}
+
+impl SegmentToggle for DirectoryConfig {
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+}
+
+impl SegmentToggle for GitConfig {
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+}
+
+impl SegmentToggle for TimeConfig {
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+}
+
+impl SegmentToggle for UsageConfig {
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+}
+
+impl SegmentToggle for CostConfig {
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+}
+
+impl SegmentToggle for VersionConfig {
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+}
+
+impl SegmentToggle for SpendConfig {
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+}
+
+impl SegmentToggle for LinesConfig {
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+}
+
+impl SegmentToggle for SessionConfig {
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+}
+
+impl SegmentToggle for BlockConfig {
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+}
+
+impl SegmentToggle for OutputStyleConfig {
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+}
+
+/// Accept either `time = true` or a full `[segments.time]` table.
+fn bool_or_options<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: SegmentToggle + Deserialize<'de>,
+{
+    // Going through a value instead of an untagged enum keeps the error of a bad option
+    match toml::Value::deserialize(deserializer)? {
+        toml::Value::Boolean(enabled) => {
+            let mut options = T::default();
+            options.set_enabled(enabled);
+            Ok(options)
+        }
+        value => T::deserialize(value).map_err(serde::de::Error::custom),
+    }
 }
 
 // Data structures compatible with existing main.rs
-#[derive(Deserialize)]
+// Every field has a default so a payload missing some of them still renders
+#[derive(Default, Deserialize)]
+#[serde(default)]
 pub struct Model {
+    /// Model id such as `claude-opus-4-1-20250805`, possibly with a `[1m]` context suffix
+    pub id: String,
     pub display_name: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Default, Deserialize)]
+#[serde(default)]
 pub struct Workspace {
     pub current_dir: String,
+    /// Where Claude Code was started; `current_dir` moves when Claude `cd`s
+    pub project_dir: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct OutputStyle {
+    pub name: String,
+}
+
+#[derive(Default, Deserialize)]
+pub struct CostInfo {
+    pub total_cost_usd: Option<f64>,
+    pub total_duration_ms: Option<u64>,
+    pub total_api_duration_ms: Option<u64>,
+    pub total_lines_added: Option<u32>,
+    pub total_lines_removed: Option<u32>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
 pub struct InputData {
     pub model: Model,
     pub workspace: Workspace,
     pub transcript_path: String,
+    pub cost: Option<CostInfo>,
+    pub output_style: OutputStyle,
+    /// Claude Code version, e.g. `1.0.80`
+    pub version: String,
+}
+
+impl InputData {
+    /// Read the statusline payload, keeping whatever could be read.
+    ///
+    /// Empty or malformed input yields the defaults and a field of the wrong shape
+    /// only loses that field; either way the error is returned next to the data.
+    pub fn parse_lenient(raw: &str) -> (Self, Option<String>) {
+        let (mut input, error) = match serde_json::from_str::<Self>(raw) {
+            Ok(input) => (input, None),
+            Err(error) => {
+                let partial = serde_json::from_str::<serde_json::Value>(raw)
+                    .ok()
+                    .map(|value| Self::from_fields(&value))
+                    .unwrap_or_default();
+                (partial, Some(error.to_string()))
+            }
+        };
+
+        // Claude Code starts the statusline command in the workspace directory
+        if input.workspace.current_dir.is_empty() {
+            if let Ok(dir) = std::env::current_dir() {
+                input.workspace.current_dir = dir.to_string_lossy().into_owned();
+            }
+        }
+        (input, error)
+    }
+
+    fn from_fields(value: &serde_json::Value) -> Self {
+        fn field<T: serde::de::DeserializeOwned + Default>(
+            value: &serde_json::Value,
+            name: &str,
+        ) -> T {
+            value
+                .get(name)
+                .and_then(|field| serde_json::from_value(field.clone()).ok())
+                .unwrap_or_default()
+        }
+
+        Self {
+            model: field(value, "model"),
+            workspace: field(value, "workspace"),
+            transcript_path: field(value, "transcript_path"),
+            cost: field(value, "cost"),
+            output_style: field(value, "output_style"),
+            version: field(value, "version"),
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -52,31 +436,138 @@ pub struct Usage {
 
 #[derive(Deserialize)]
 pub struct MessageContent {
-    #[serde(rename = "type")]
+    #[serde(rename = "type", default)]
     pub content_type: String,
     pub text: Option<String>,
+    // tool_use blocks
+    pub name: Option<String>,
+    pub input: Option<serde_json::Value>,
 }
 
 #[derive(Deserialize)]
 pub struct Message {
     pub id: Option<String>,
     #[serde(rename = "type")]
-    pub message_type: String,
-    pub role: String,
+    pub message_type: Option<String>,
+    pub role: Option<String>,
     pub model: Option<String>,
-    pub content: Option<Vec<MessageContent>>,
+    /// Plain string content is read as a single text block
+    #[serde(default, deserialize_with = "content_blocks")]
+    pub content: Vec<MessageContent>,
     pub usage: Option<Usage>,
 }
 
 #[derive(Deserialize)]
 pub struct TranscriptEntry {
-    #[serde(rename = "type")]
+    /// `user`, `assistant`, `system`, `summary`, `file-history-snapshot`, ... (empty when missing)
+    #[serde(rename = "type", default)]
     pub entry_type: String,
+    // A message we can't model shouldn't hide the rest of the entry (flags, timestamp)
+    #[serde(default, deserialize_with = "message_or_none")]
     pub message: Option<Message>,
     // 智谱清言特有的字段
     pub cwd: Option<String>,
     #[serde(rename = "gitBranch")]
     pub git_branch: Option<String>,
     pub uuid: Option<String>,
+    #[serde(rename = "parentUuid")]
+    pub parent_uuid: Option<String>,
+    /// API request that produced the message; shared by every line of a streamed response
+    #[serde(rename = "requestId")]
+    pub request_id: Option<String>,
+    /// Set on entries written by sub-agents (Task tool), which run in their own context
+    #[serde(rename = "isSidechain", default)]
+    pub is_sidechain: bool,
     pub timestamp: Option<String>,
+    /// e.g. `compact_boundary` on `system` entries
+    pub subtype: Option<String>,
+    /// Set on the user message carrying the summary that replaces a compacted conversation
+    #[serde(rename = "isCompactSummary", default)]
+    pub is_compact_summary: bool,
+}
+
+fn message_or_none<'de, D>(deserializer: D) -> Result<Option<Message>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(value.and_then(|value| serde_json::from_value(value).ok()))
+}
+
+fn content_blocks<'de, D>(deserializer: D) -> Result<Vec<MessageContent>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let blocks = match Option::<serde_json::Value>::deserialize(deserializer)? {
+        Some(serde_json::Value::String(text)) => vec![MessageContent {
+            content_type: "text".to_string(),
+            text: Some(text),
+            name: None,
+            input: None,
+        }],
+        // Blocks of a shape we don't know are dropped, not the whole message
+        Some(serde_json::Value::Array(values)) => values
+            .into_iter()
+            .filter_map(|value| serde_json::from_value(value).ok())
+            .collect(),
+        _ => Vec::new(),
+    };
+    Ok(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process_dir() -> String {
+        std::env::current_dir()
+            .unwrap()
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_parse_lenient_empty_and_invalid() {
+        for raw in ["", "{\"model\": {\"display_name\": \"Opus\""] {
+            let (input, error) = InputData::parse_lenient(raw);
+            assert!(error.is_some(), "{:?} should report an error", raw);
+            assert!(input.model.display_name.is_empty());
+            assert!(input.transcript_path.is_empty());
+            assert_eq!(input.workspace.current_dir, process_dir());
+        }
+    }
+
+    #[test]
+    fn test_parse_lenient_wrong_field_type() {
+        let raw =
+            r#"{"model":5,"workspace":{"current_dir":"/work/crate"},"transcript_path":"/t.jsonl"}"#;
+        let (input, error) = InputData::parse_lenient(raw);
+
+        assert!(error.is_some());
+        assert!(input.model.display_name.is_empty());
+        assert_eq!(input.workspace.current_dir, "/work/crate");
+        assert_eq!(input.transcript_path, "/t.jsonl");
+    }
+
+    #[test]
+    fn test_parse_lenient_current_dir_fallback() {
+        let (input, error) = InputData::parse_lenient(r#"{"model":{"display_name":"Opus"}}"#);
+
+        assert!(error.is_none());
+        assert_eq!(input.model.display_name, "Opus");
+        assert_eq!(input.workspace.current_dir, process_dir());
+    }
+
+    #[test]
+    fn test_segments_accept_bool_or_options() {
+        let config: Config = toml::from_str(
+            "[segments]\nlines = false\nsession = true\n\n[segments.block]\nenabled = true\n",
+        )
+        .unwrap();
+
+        assert!(!config.segments.lines.enabled);
+        assert!(config.segments.session.enabled);
+        assert!(config.segments.block.enabled);
+        assert!(!config.segments.output_style.enabled);
+    }
 }
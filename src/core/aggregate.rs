@@ -0,0 +1,255 @@
+use crate::core::blocks::BlockMessage;
+use crate::core::history::{self, UsageRecord};
+use crate::core::transcript;
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Bumped whenever the cached layout or the way records are priced changes.
+const INDEX_VERSION: u32 = 3;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageTotals {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cost: f64,
+    pub messages: u64,
+}
+
+impl UsageTotals {
+    pub fn add_record(&mut self, record: &UsageRecord) {
+        self.input_tokens += record.input_tokens;
+        self.output_tokens += record.output_tokens;
+        self.cache_creation_tokens += record.cache_creation_tokens;
+        self.cache_read_tokens += record.cache_read_tokens;
+        self.cost += record.cost;
+        self.messages += 1;
+    }
+
+    pub fn add(&mut self, other: &UsageTotals) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cost += other.cost;
+        self.messages += other.messages;
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+    }
+}
+
+/// Usage of one project on one local calendar day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyUsage {
+    pub date: NaiveDate,
+    pub project: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Today,
+    /// Monday through today
+    Week,
+    Month,
+}
+
+impl Period {
+    /// Inclusive date range of the period containing `today`.
+    pub fn range(self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let start = match self {
+            Period::Today => today,
+            Period::Week => today - Duration::days(today.weekday().num_days_from_monday() as i64),
+            Period::Month => today.with_day(1).unwrap_or(today),
+        };
+        (start, today)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Period::Today => "today",
+            Period::Week => "week",
+            Period::Month => "month",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexedTranscript {
+    modified: u64,
+    size: u64,
+    days: Vec<DailyUsage>,
+    /// Every message, for the 5-hour blocks
+    messages: Vec<BlockMessage>,
+}
+
+/// Per-day, per-project totals of every transcript under `~/.claude/projects`.
+///
+/// The index is cached in `~/.claude/ccline/usage-index.json`; only transcripts whose
+/// size or modification time changed since the last run are parsed again.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UsageIndex {
+    version: u32,
+    transcripts: HashMap<String, IndexedTranscript>,
+}
+
+impl UsageIndex {
+    pub fn load() -> Self {
+        let cache_path = index_path();
+        let mut index = cache_path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<Self>(&content).ok())
+            .filter(|index| index.version == INDEX_VERSION)
+            .unwrap_or_default();
+
+        if index.refresh() {
+            if let Some(path) = cache_path {
+                index.save(&path);
+            }
+        }
+        index
+    }
+
+    /// Re-read changed transcripts and forget deleted ones. Returns whether anything changed.
+    fn refresh(&mut self) -> bool {
+        self.version = INDEX_VERSION;
+        let mut changed = false;
+        let mut seen = HashSet::new();
+
+        for path in transcript::find_transcripts(None) {
+            let key = path.to_string_lossy().into_owned();
+            let Some((modified, size)) = file_stamp(&path) else {
+                continue;
+            };
+            seen.insert(key.clone());
+
+            let up_to_date = self
+                .transcripts
+                .get(&key)
+                .is_some_and(|cached| cached.modified == modified && cached.size == size);
+            if !up_to_date {
+                let records = history::read_usage_records(&path);
+                self.transcripts.insert(
+                    key,
+                    IndexedTranscript {
+                        modified,
+                        size,
+                        days: summarize_by_day(&records),
+                        messages: records.iter().map(BlockMessage::from).collect(),
+                    },
+                );
+                changed = true;
+            }
+        }
+
+        let before = self.transcripts.len();
+        self.transcripts.retain(|key, _| seen.contains(key));
+        changed || self.transcripts.len() != before
+    }
+
+    fn save(&self, path: &Path) {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let Ok(content) = serde_json::to_string(self) else {
+            return;
+        };
+        // Other sessions render concurrently; they must never read a half-written index
+        let staging = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        if fs::write(&staging, content).is_err() || fs::rename(&staging, path).is_err() {
+            let _ = fs::remove_file(&staging);
+        }
+    }
+
+    pub fn daily_usage(&self) -> impl Iterator<Item = &DailyUsage> {
+        self.transcripts
+            .values()
+            .flat_map(|transcript| transcript.days.iter())
+    }
+
+    /// Every message across all transcripts, oldest first.
+    pub fn block_messages(&self) -> Vec<BlockMessage> {
+        let mut messages: Vec<BlockMessage> = self
+            .transcripts
+            .values()
+            .flat_map(|transcript| transcript.messages.iter().copied())
+            .collect();
+        messages.sort_by_key(|message| message.timestamp);
+        messages
+    }
+
+    /// Totals over an inclusive date range, across all projects.
+    pub fn totals_between(&self, from: NaiveDate, to: NaiveDate) -> UsageTotals {
+        let mut totals = UsageTotals::default();
+        for day in self.daily_usage() {
+            if day.date >= from && day.date <= to {
+                totals.add(&day.totals);
+            }
+        }
+        totals
+    }
+
+    pub fn period_totals(&self, period: Period) -> UsageTotals {
+        let (from, to) = period.range(Local::now().date_naive());
+        self.totals_between(from, to)
+    }
+}
+
+/// Group records by the local date they happened on and their project.
+pub fn summarize_by_day(records: &[UsageRecord]) -> Vec<DailyUsage> {
+    let mut days: BTreeMap<(NaiveDate, String), UsageTotals> = BTreeMap::new();
+    for record in records {
+        let date = record.timestamp.with_timezone(&Local).date_naive();
+        days.entry((date, record.project.clone()))
+            .or_default()
+            .add_record(record);
+    }
+
+    days.into_iter()
+        .map(|((date, project), totals)| DailyUsage {
+            date,
+            project,
+            totals,
+        })
+        .collect()
+}
+
+fn index_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claude").join("ccline").join("usage-index.json"))
+}
+
+fn file_stamp(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_millis() as u64;
+    Some((modified, metadata.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_period_ranges() {
+        // A Thursday
+        let today = NaiveDate::from_ymd_opt(2025, 8, 14).unwrap();
+        let date = |d| NaiveDate::from_ymd_opt(2025, 8, d).unwrap();
+
+        assert_eq!(Period::Today.range(today), (today, today));
+        assert_eq!(Period::Week.range(today), (date(11), today));
+        assert_eq!(Period::Month.range(today), (date(1), today));
+    }
+}
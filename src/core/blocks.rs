@@ -0,0 +1,180 @@
+use crate::core::history::UsageRecord;
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Claude subscription limits reset on rolling 5-hour windows.
+pub const BLOCK_DURATION_HOURS: i64 = 5;
+
+/// What the blocks need from one assistant message, kept for every message in the usage index.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BlockMessage {
+    pub timestamp: DateTime<Utc>,
+    pub tokens: u64,
+    pub cost: f64,
+}
+
+impl From<&UsageRecord> for BlockMessage {
+    fn from(record: &UsageRecord) -> Self {
+        Self {
+            timestamp: record.timestamp,
+            tokens: record.total_tokens(),
+            cost: record.cost,
+        }
+    }
+}
+
+/// One 5-hour usage window, starting at the top of the hour of its first message.
+#[derive(Debug, Clone)]
+pub struct UsageBlock {
+    pub start: DateTime<Utc>,
+    pub last_activity: DateTime<Utc>,
+    pub tokens: u64,
+    pub cost: f64,
+    pub messages: usize,
+}
+
+impl UsageBlock {
+    pub fn end(&self) -> DateTime<Utc> {
+        self.start + Duration::hours(BLOCK_DURATION_HOURS)
+    }
+
+    /// A block stays active until it expires or five hours pass without activity.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        now < self.end() && now - self.last_activity < Duration::hours(BLOCK_DURATION_HOURS)
+    }
+
+    pub fn remaining(&self, now: DateTime<Utc>) -> Duration {
+        (self.end() - now).max(Duration::zero())
+    }
+
+    /// Cost at the end of the block if spending continues at the rate seen so far.
+    pub fn projected_cost(&self, now: DateTime<Utc>) -> f64 {
+        let elapsed = (now.min(self.end()) - self.start).num_seconds();
+        if elapsed <= 0 {
+            return self.cost;
+        }
+        self.cost / elapsed as f64 * (BLOCK_DURATION_HOURS * 3600) as f64
+    }
+}
+
+/// Group time-ordered messages into 5-hour blocks.
+///
+/// Where a block starts depends on every message since the last gap of five hours or more,
+/// so `messages` has to reach back that far, however long the working day was.
+pub fn identify_blocks(messages: &[BlockMessage]) -> Vec<UsageBlock> {
+    let mut blocks: Vec<UsageBlock> = Vec::new();
+
+    for record in messages {
+        let starts_new_block = match blocks.last() {
+            Some(block) => {
+                record.timestamp >= block.end()
+                    || record.timestamp - block.last_activity
+                        >= Duration::hours(BLOCK_DURATION_HOURS)
+            }
+            None => true,
+        };
+
+        if starts_new_block {
+            let start = record
+                .timestamp
+                .duration_trunc(Duration::hours(1))
+                .unwrap_or(record.timestamp);
+            blocks.push(UsageBlock {
+                start,
+                last_activity: record.timestamp,
+                tokens: 0,
+                cost: 0.0,
+                messages: 0,
+            });
+        }
+
+        let block = blocks.last_mut().expect("a block was just pushed");
+        block.last_activity = block.last_activity.max(record.timestamp);
+        block.tokens += record.tokens;
+        block.cost += record.cost;
+        block.messages += 1;
+    }
+
+    blocks
+}
+
+/// The block covering `now`, if any usage has happened in it.
+pub fn active_block(messages: &[BlockMessage], now: DateTime<Utc>) -> Option<UsageBlock> {
+    identify_blocks(messages)
+        .pop()
+        .filter(|block| block.is_active(now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::transcript::parse_timestamp;
+
+    fn record(timestamp: &str, cost: f64) -> BlockMessage {
+        BlockMessage {
+            timestamp: parse_timestamp(timestamp).unwrap(),
+            tokens: 150,
+            cost,
+        }
+    }
+
+    #[test]
+    fn test_blocks_split_on_expiry_and_gaps() {
+        let records = vec![
+            record("2025-08-12T09:20:00Z", 1.0),
+            record("2025-08-12T13:59:00Z", 1.0),
+            // Past 14:00, the end of the first block
+            record("2025-08-12T14:05:00Z", 1.0),
+            // More than five hours after the last message
+            record("2025-08-12T19:30:00Z", 1.0),
+        ];
+
+        let blocks = identify_blocks(&records);
+        let starts: Vec<String> = blocks.iter().map(|b| b.start.to_rfc3339()).collect();
+        assert_eq!(
+            starts,
+            [
+                "2025-08-12T09:00:00+00:00",
+                "2025-08-12T14:00:00+00:00",
+                "2025-08-12T19:00:00+00:00",
+            ]
+        );
+        assert_eq!(blocks[0].messages, 2);
+        assert_eq!(blocks[0].tokens, 300);
+    }
+
+    #[test]
+    fn test_active_block_projection() {
+        let records = vec![
+            record("2025-08-12T09:10:00Z", 1.0),
+            record("2025-08-12T09:50:00Z", 1.5),
+        ];
+        let now = parse_timestamp("2025-08-12T10:00:00Z").unwrap();
+
+        let block = active_block(&records, now).unwrap();
+        assert_eq!(block.remaining(now), Duration::hours(4));
+        assert!((block.projected_cost(now) - 12.5).abs() < 1e-9);
+
+        let later = parse_timestamp("2025-08-12T14:00:00Z").unwrap();
+        assert!(active_block(&records, later).is_none());
+    }
+
+    #[test]
+    fn test_long_day_keeps_block_boundaries() {
+        // A message every 30 minutes from 08:00 to 20:00 with no 5-hour gap
+        let day = parse_timestamp("2025-08-12T08:00:00Z").unwrap();
+        let records: Vec<BlockMessage> = (0..=24)
+            .map(|i| BlockMessage {
+                timestamp: day + Duration::minutes(30 * i),
+                ..record("2025-08-12T08:00:00Z", 1.0)
+            })
+            .collect();
+        let now = parse_timestamp("2025-08-12T20:10:00Z").unwrap();
+
+        let block = active_block(&records, now).unwrap();
+
+        // Blocks run 08:00-13:00, 13:00-18:00 and 18:00-23:00
+        assert_eq!(block.start.to_rfc3339(), "2025-08-12T18:00:00+00:00");
+        assert_eq!(block.messages, 5);
+    }
+}
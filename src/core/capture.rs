@@ -0,0 +1,138 @@
+use crate::config::InputData;
+use crate::core::segments::git::GitInfo;
+use chrono::Local;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const PAYLOAD_FILE: &str = "payload.json";
+const TRANSCRIPT_FILE: &str = "transcript.jsonl";
+const GIT_FILE: &str = "git.json";
+
+/// One render's input, saved by `--record` and rendered again by `ccline replay`.
+pub struct Capture {
+    /// The capture directory
+    pub path: PathBuf,
+    /// The stdin payload exactly as received
+    pub payload: String,
+    /// Git state at recording time, `None` outside a repository
+    pub git: Option<GitInfo>,
+}
+
+/// Save the payload, its transcript and the git state the statusline was rendered with
+/// (`None` outside a repository) into a new timestamped directory under `dir`. Returns the
+/// capture directory.
+pub fn record(
+    dir: &Path,
+    payload: &str,
+    input: &InputData,
+    git: Option<&GitInfo>,
+) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let stamp = Local::now().format("%Y%m%d-%H%M%S%.3f").to_string();
+    let mut path = dir.join(&stamp);
+    // Two renders within the same millisecond still get their own directory
+    let mut n = 1;
+    loop {
+        match fs::create_dir(&path) {
+            Ok(()) => break,
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                path = dir.join(format!("{}-{}", stamp, n));
+                n += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    fs::write(path.join(PAYLOAD_FILE), payload)?;
+    // The whole transcript, so session totals replay the same as they rendered
+    match fs::copy(&input.transcript_path, path.join(TRANSCRIPT_FILE)) {
+        Ok(_) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    fs::write(path.join(GIT_FILE), serde_json::to_string_pretty(&git)?)?;
+
+    Ok(path)
+}
+
+impl Capture {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let payload = fs::read_to_string(path.join(PAYLOAD_FILE))?;
+        let git = match fs::read_to_string(path.join(GIT_FILE)) {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            payload,
+            git,
+        })
+    }
+
+    /// The payload, pointed at the captured transcript instead of the original one.
+    pub fn input(&self) -> (InputData, Option<String>) {
+        let (mut input, error) = InputData::parse_lenient(&self.payload);
+        let transcript = self.path.join(TRANSCRIPT_FILE);
+        input.transcript_path = if transcript.exists() {
+            transcript.to_string_lossy().into_owned()
+        } else {
+            String::new()
+        };
+        (input, error)
+    }
+}
+
+/// `dir` itself if it is a capture, otherwise the captures directly inside it, oldest first.
+pub fn find_captures(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    if dir.join(PAYLOAD_FILE).is_file() {
+        return Ok(vec![dir.to_path_buf()]);
+    }
+
+    let mut captures: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.join(PAYLOAD_FILE).is_file())
+        .collect();
+    // Directory names start with the recording time
+    captures.sort();
+    Ok(captures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_load() {
+        let root = std::env::temp_dir().join(format!("ccline_capture_test_{}", std::process::id()));
+        let transcript = root.join("session.jsonl");
+        fs::create_dir_all(&root).unwrap();
+        let lines: Vec<String> = (0..1200).map(|i| format!(r#"{{"n":{}}}"#, i)).collect();
+        fs::write(&transcript, lines.join("\n")).unwrap();
+        let payload = format!(
+            r#"{{"model":{{"display_name":"Opus"}},"workspace":{{"current_dir":"{}"}},"transcript_path":"{}"}}"#,
+            root.display(),
+            transcript.display()
+        );
+        let (input, _) = InputData::parse_lenient(&payload);
+
+        let captures = root.join("captures");
+        let recorded = record(&captures, &payload, &input, None).unwrap();
+        let again = record(&captures, &payload, &input, None).unwrap();
+        let found = find_captures(&captures).unwrap();
+        let capture = Capture::load(&found[0]).unwrap();
+        let (replayed, error) = capture.input();
+        let copied = fs::read_to_string(&replayed.transcript_path).unwrap();
+        fs::remove_dir_all(&root).ok();
+
+        assert_eq!(found, vec![recorded.clone(), again]);
+        assert_eq!(capture.payload, payload);
+        assert!(error.is_none());
+        assert_eq!(
+            Path::new(&replayed.transcript_path),
+            recorded.join(TRANSCRIPT_FILE)
+        );
+        assert_eq!(copied, lines.join("\n"));
+    }
+}
@@ -0,0 +1,88 @@
+use crate::core::segments::cost::message_cost;
+use crate::core::transcript::{self, parse_timestamp};
+use chrono::{DateTime, Utc};
+use std::path::Path;
+
+/// Token usage of one assistant response, taken from any transcript under `~/.claude/projects`.
+#[derive(Debug, Clone)]
+pub struct UsageRecord {
+    pub timestamp: DateTime<Utc>,
+    pub project: String,
+    pub session_id: String,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    /// Priced the same way as the cost segment prices the current session
+    pub cost: f64,
+}
+
+impl UsageRecord {
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+    }
+}
+
+/// Usage records from every transcript, oldest first.
+///
+/// With `since` set, transcripts last modified before it are not read at all and
+/// older records are dropped.
+pub fn load_usage_records(since: Option<DateTime<Utc>>) -> Vec<UsageRecord> {
+    let mut records: Vec<UsageRecord> = transcript::find_transcripts(since.map(Into::into))
+        .iter()
+        .flat_map(read_usage_records)
+        .filter(|record| since.is_none_or(|since| record.timestamp >= since))
+        .collect();
+
+    records.sort_by_key(|record| record.timestamp);
+    records
+}
+
+pub fn read_usage_records<P: AsRef<Path>>(transcript_path: P) -> Vec<UsageRecord> {
+    let path = transcript_path.as_ref();
+    let session_id = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default()
+        .to_string();
+    // Encoded project path, e.g. `-home-me-work-crate`, used when entries carry no `cwd`
+    let project_dir = path
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .to_string();
+
+    let mut records = Vec::new();
+    for entry in transcript::dedupe_responses(transcript::read_entries(path)) {
+        if entry.entry_type != "assistant" {
+            continue;
+        }
+        let Some(timestamp) = entry.timestamp.as_deref().and_then(parse_timestamp) else {
+            continue;
+        };
+        let Some(message) = entry.message else {
+            continue;
+        };
+        let Some(usage) = &message.usage else {
+            continue;
+        };
+
+        let model = message.model.clone().unwrap_or_default();
+
+        records.push(UsageRecord {
+            timestamp,
+            project: entry.cwd.unwrap_or_else(|| project_dir.clone()),
+            session_id: session_id.clone(),
+            input_tokens: usage.input_tokens.unwrap_or(0) as u64,
+            output_tokens: usage.output_tokens.unwrap_or(0) as u64,
+            cache_creation_tokens: usage.cache_creation_input_tokens.unwrap_or(0) as u64,
+            cache_read_tokens: usage.cache_read_input_tokens.unwrap_or(0) as u64,
+            cost: message_cost(usage, &model),
+            model,
+        });
+    }
+
+    records
+}
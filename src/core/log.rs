@@ -0,0 +1,29 @@
+use chrono::Local;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// `~/.claude/ccline/ccline.log`
+pub fn log_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claude").join("ccline").join("ccline.log"))
+}
+
+/// `~/.claude/ccline/debug.jsonl`, where `--debug` traces go
+pub fn debug_log_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claude").join("ccline").join("debug.jsonl"))
+}
+
+/// Append a timestamped line to the log file.
+///
+/// The statusline has no other place to report problems, so failures to write are ignored.
+pub fn append(message: &str) {
+    let Some(path) = log_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&path) {
+        let _ = writeln!(file, "{} {}", Local::now().to_rfc3339(), message);
+    }
+}
@@ -1,4 +1,12 @@
+pub mod aggregate;
+pub mod blocks;
+pub mod capture;
+pub mod history;
+pub mod log;
 pub mod segments;
 pub mod statusline;
+pub mod theme;
+pub mod trace;
+pub mod transcript;
 
 pub use statusline::StatusLineGenerator;
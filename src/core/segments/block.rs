@@ -0,0 +1,65 @@
+use super::cost::format_cost;
+use super::session::format_duration;
+use super::Segment;
+use crate::config::{BlockConfig, InputData};
+use crate::core::aggregate::UsageIndex;
+use crate::core::blocks::{self, UsageBlock};
+use chrono::Utc;
+
+pub struct BlockSegment {
+    enabled: bool,
+}
+
+impl BlockSegment {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    pub fn from_config(config: &BlockConfig) -> Self {
+        Self::new(config.enabled)
+    }
+
+    /// The current 5-hour block across all sessions, if one is active.
+    ///
+    /// Built from the usage index, so only transcripts that changed since the last render
+    /// are read again.
+    pub fn get_active_block(&self) -> Option<UsageBlock> {
+        blocks::active_block(&UsageIndex::load().block_messages(), Utc::now())
+    }
+}
+
+impl Segment for BlockSegment {
+    fn render(&self, _input: &InputData) -> String {
+        if !self.enabled {
+            return String::new();
+        }
+
+        let Some(block) = self.get_active_block() else {
+            return String::new();
+        };
+
+        let now = Utc::now();
+        let remaining = block.remaining(now).to_std().unwrap_or_default();
+        format!(
+            "\u{f0520} {} · {} tokens · {} left · ~{}",
+            format_cost(block.cost),
+            format_token_count(block.tokens),
+            format_duration(remaining),
+            format_cost(block.projected_cost(now))
+        )
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+fn format_token_count(tokens: u64) -> String {
+    if tokens >= 1_000_000 {
+        format!("{:.1}M", tokens as f64 / 1_000_000.0)
+    } else if tokens >= 1000 {
+        format!("{:.1}k", tokens as f64 / 1000.0)
+    } else {
+        tokens.to_string()
+    }
+}
@@ -1,163 +1,346 @@
-use super::Segment;
-use crate::config::{InputData, TranscriptEntry};
+use super::{Segment, Severity};
+use crate::config::{BudgetConfig, CostConfig, InputData, Usage};
+use crate::core::aggregate::{Period, UsageIndex};
+use crate::core::transcript;
 use std::collections::HashMap;
-use std::fs;
-use std::io::{BufRead, BufReader};
 use std::path::Path;
 
 // 费用计算配置 (价格每1M tokens)
 fn get_pricing() -> HashMap<&'static str, (f64, f64)> {
     [
-        ("claude-3-5-sonnet-20241022", (3.00, 15.00)),   // 输入$3/1M, 输出$15/1M
-        ("claude-3-haiku-20240307", (0.25, 1.25)),        // 输入$0.25/1M, 输出$1.25/1M
-        ("gpt-4", (30.00, 60.00)),                       // 输入$30/1M, 输出$60/1M
-        ("gpt-3.5-turbo", (0.50, 1.50)),                 // 输入$0.50/1M, 输出$1.50/1M
-        ("glm-4", (5.00, 25.00)),                        // 智谱清言GLM-4示例价格
-        ("glm-4.5", (4.00, 16.00)),                      // 智谱清言GLM-4.5价格：输入4元/1M, 输出16元/1M
-    ].iter().cloned().collect()
+        ("claude-3-5-sonnet-20241022", (3.00, 15.00)), // 输入$3/1M, 输出$15/1M
+        ("claude-3-haiku-20240307", (0.25, 1.25)),     // 输入$0.25/1M, 输出$1.25/1M
+        ("gpt-4", (30.00, 60.00)),                     // 输入$30/1M, 输出$60/1M
+        ("gpt-3.5-turbo", (0.50, 1.50)),               // 输入$0.50/1M, 输出$1.50/1M
+        ("glm-4", (5.00, 25.00)),                      // 智谱清言GLM-4示例价格
+        ("glm-4.5", (4.00, 16.00)), // 智谱清言GLM-4.5价格：输入4元/1M, 输出16元/1M
+    ]
+    .iter()
+    .cloned()
+    .collect()
+}
+
+// 从模型名称中提取基础名称用于定价查找
+pub fn pricing_model(model_display: &str) -> &'static str {
+    if model_display.contains("claude-3-5-sonnet") {
+        "claude-3-5-sonnet-20241022"
+    } else if model_display.contains("haiku") {
+        "claude-3-haiku-20240307"
+    } else if model_display.contains("gpt-4") {
+        "gpt-4"
+    } else if model_display.contains("gpt-3.5") {
+        "gpt-3.5-turbo"
+    } else if model_display.contains("glm-4.5") {
+        "glm-4.5"
+    } else if model_display.contains("glm-4") {
+        "glm-4"
+    } else {
+        // 默认使用claude-3-5-sonnet的价格
+        "claude-3-5-sonnet-20241022"
+    }
+}
+
+// 根据模型类型计算单条消息的计费输入/输出token
+pub fn billable_tokens(usage: &Usage, model_name: &str) -> (u32, u32) {
+    match model_name {
+        "glm-4.5" | "glm-4" => {
+            // GLM模型：如果total_tokens存在，估算输入输出比例
+            match usage.total_tokens {
+                Some(total) if total > 0 => {
+                    // 简单估算：假设70%输入，30%输出
+                    let estimated_input = (total as f64 * 0.7) as u32;
+                    (estimated_input, total - estimated_input)
+                }
+                _ => (
+                    usage.input_tokens.unwrap_or(0),
+                    usage.output_tokens.unwrap_or(0),
+                ),
+            }
+        }
+        "gpt-4" | "gpt-3.5-turbo" => {
+            // GPT模型使用标准字段
+            (
+                usage.input_tokens.unwrap_or(0),
+                usage.output_tokens.unwrap_or(0),
+            )
+        }
+        _ => {
+            // Claude模型（以及默认情况）使用标准字段，输入包含缓存
+            (
+                usage.input_tokens.unwrap_or(0)
+                    + usage.cache_creation_input_tokens.unwrap_or(0)
+                    + usage.cache_read_input_tokens.unwrap_or(0),
+                usage.output_tokens.unwrap_or(0),
+            )
+        }
+    }
+}
+
+// 计算总费用
+pub fn calculate_cost(input_tokens: u64, output_tokens: u64, model_name: &str) -> f64 {
+    let pricing = get_pricing();
+    if let Some((input_price, output_price)) = pricing.get(model_name) {
+        let input_cost = (input_tokens as f64 / 1_000_000.0) * input_price;
+        let output_cost = (output_tokens as f64 / 1_000_000.0) * output_price;
+        input_cost + output_cost
+    } else {
+        0.0
+    }
+}
+
+/// Cost of one assistant message, priced by the model that produced it.
+///
+/// Every cost figure (session, block, daily totals, reports) goes through here so they agree.
+pub fn message_cost(usage: &Usage, model: &str) -> f64 {
+    let pricing = pricing_model(model);
+    let (input, output) = billable_tokens(usage, pricing);
+    calculate_cost(input as u64, output as u64, pricing)
+}
+
+// 根据费用大小选择显示单位
+pub fn format_cost(cost: f64) -> String {
+    if cost < 0.01 {
+        format!("${:.4}", cost)
+    } else if cost < 1.0 {
+        format!("${:.3}", cost)
+    } else {
+        format!("${:.2}", cost)
+    }
 }
 
 pub struct CostSegment {
     enabled: bool,
+    show_subagents: bool,
+    precision: Option<usize>,
+    budget: Option<BudgetConfig>,
+}
+
+/// Cost of the current session.
+#[derive(Debug, Default, PartialEq)]
+pub struct SessionCost {
+    /// Everything, sub-agents included
+    pub total: f64,
+    /// The part spent by sub-agents (sidechain messages)
+    pub subagents: f64,
+}
+
+/// Where spend stands against the configured budgets.
+#[derive(Debug, PartialEq)]
+pub struct BudgetStatus {
+    pub severity: Severity,
+    /// Names of the budgets that have been used up
+    pub exceeded: Vec<&'static str>,
 }
 
 impl CostSegment {
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
-    }
-    
-    // 从模型名称中提取基础名称用于定价查找
-    fn extract_model_name(model_display: &str) -> &'static str {
-        if model_display.contains("claude-3-5-sonnet") {
-            "claude-3-5-sonnet-20241022"
-        } else if model_display.contains("haiku") {
-            "claude-3-haiku-20240307"
-        } else if model_display.contains("gpt-4") {
-            "gpt-4"
-        } else if model_display.contains("gpt-3.5") {
-            "gpt-3.5-turbo"
-        } else if model_display.contains("glm-4.5") {
-            "glm-4.5"
-        } else if model_display.contains("glm-4") {
-            "glm-4"
-        } else {
-            // 默认使用claude-3-5-sonnet的价格
-            "claude-3-5-sonnet-20241022"
+        Self {
+            enabled,
+            show_subagents: false,
+            precision: None,
+            budget: None,
         }
     }
-    
-    // 计算总费用
-    fn calculate_total_cost(&self, input_tokens: u32, output_tokens: u32, model_name: &str) -> f64 {
-        let pricing = get_pricing();
-        if let Some((input_price, output_price)) = pricing.get(model_name) {
-            let input_cost = (input_tokens as f64 / 1_000_000.0) * input_price;
-            let output_cost = (output_tokens as f64 / 1_000_000.0) * output_price;
-            input_cost + output_cost
-        } else {
-            0.0
+
+    pub fn from_config(config: &CostConfig) -> Self {
+        Self::new(config.enabled)
+            .with_subagents(config.show_subagents)
+            .with_precision(config.precision)
+    }
+
+    pub fn with_subagents(mut self, show_subagents: bool) -> Self {
+        self.show_subagents = show_subagents;
+        self
+    }
+
+    /// Fixed number of decimal places instead of the adaptive `format_cost`.
+    pub fn with_precision(mut self, precision: Option<usize>) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    fn format_amount(&self, cost: f64) -> String {
+        match self.precision {
+            Some(precision) => format!("${:.*}", precision, cost),
+            None => format_cost(cost),
+        }
+    }
+
+    pub fn with_budget(mut self, budget: BudgetConfig) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Render the segment along with how close spend is to the budgets.
+    pub fn render_with_severity(&self, input: &InputData) -> (String, Severity) {
+        if !self.enabled {
+            return (String::new(), Severity::Normal);
+        }
+
+        let session_cost =
+            self.parse_transcript_cost(&input.transcript_path, &input.model.display_name);
+        let mut content = match &session_cost {
+            Some(cost) if cost.total > 0.0 => {
+                format!("\u{f09d3} {}", self.format_amount(cost.total))
+            }
+            _ if self.precision.is_some() => format!("\u{f09d3} {}", self.format_amount(0.0)),
+            _ => String::from("\u{f09d3} $0.000"),
+        };
+        if let Some(cost) = session_cost.as_ref().filter(|cost| cost.subagents > 0.0) {
+            if self.show_subagents {
+                content.push_str(&format!(" (agents {})", self.format_amount(cost.subagents)));
+            }
+        }
+
+        let status = self.budget_status(session_cost.map_or(0.0, |cost| cost.total));
+        if !status.exceeded.is_empty() {
+            content.push_str(&format!(" \u{26a0} {}", status.exceeded.join("/")));
         }
+        (content, status.severity)
     }
-    
-    // 解析transcript文件获取完整的token使用情况（根据模型类型）
-    fn parse_transcript_costs<P: AsRef<Path>>(&self, transcript_path: P, model_name: &str) -> (u32, u32) {
-        let file = match fs::File::open(&transcript_path) {
-            Ok(file) => file,
-            Err(_) => return (0, 0),
+
+    pub fn budget_status(&self, session_cost: f64) -> BudgetStatus {
+        let Some(budget) = &self.budget else {
+            return evaluate_budgets(&[], 1.0);
         };
 
-        let reader = BufReader::new(file);
-        let lines: Vec<String> = reader.lines().collect::<Result<Vec<_>, _>>().unwrap_or_default();
-        
-        let mut total_input_tokens = 0;
-        let mut total_output_tokens = 0;
+        // Daily and monthly totals need the cross-session index, so only load it when asked
+        let index = (budget.daily.is_some() || budget.monthly.is_some()).then(UsageIndex::load);
+        let period_cost = |period| {
+            index
+                .as_ref()
+                .map_or(0.0, |index| index.period_totals(period).cost)
+        };
 
-        for line in lines.iter().rev() {
-            let line = line.trim();
-            if line.is_empty() {
+        let mut spend = Vec::new();
+        if let Some(limit) = budget.session {
+            spend.push(("session", session_cost, limit));
+        }
+        if let Some(limit) = budget.daily {
+            spend.push(("daily", period_cost(Period::Today), limit));
+        }
+        if let Some(limit) = budget.monthly {
+            spend.push(("monthly", period_cost(Period::Month), limit));
+        }
+
+        evaluate_budgets(&spend, budget.warning_threshold)
+    }
+
+    // 解析transcript文件计算会话总费用（按每条消息的模型定价，包含子代理），没有用量记录时返回None
+    fn parse_transcript_cost<P: AsRef<Path>>(
+        &self,
+        transcript_path: P,
+        default_model: &str,
+    ) -> Option<SessionCost> {
+        let mut session_cost: Option<SessionCost> = None;
+
+        let entries = transcript::dedupe_responses(transcript::read_entries(transcript_path));
+        for entry in entries {
+            if entry.entry_type != "assistant" {
                 continue;
             }
-
-            if let Ok(entry) = serde_json::from_str::<TranscriptEntry>(line) {
-                if entry.entry_type == "assistant" {
-                    if let Some(message) = &entry.message {
-                        if let Some(usage) = &message.usage {
-                            match model_name {
-                                "glm-4.5" | "glm-4" => {
-                                    // GLM模型：如果total_tokens存在，估算输入输出比例
-                                    if let Some(total) = usage.total_tokens {
-                                        if total > 0 {
-                                            // 简单估算：假设70%输入，30%输出
-                                            let estimated_input = (total as f64 * 0.7) as u32;
-                                            let estimated_output = total - estimated_input;
-                                            total_input_tokens += estimated_input;
-                                            total_output_tokens += estimated_output;
-                                        } else {
-                                            total_input_tokens += usage.input_tokens.unwrap_or(0);
-                                            total_output_tokens += usage.output_tokens.unwrap_or(0);
-                                        }
-                                    } else {
-                                        total_input_tokens += usage.input_tokens.unwrap_or(0);
-                                        total_output_tokens += usage.output_tokens.unwrap_or(0);
-                                    }
-                                },
-                                "claude-3-5-sonnet-20241022" | "claude-3-haiku-20240307" => {
-                                    // Claude模型使用标准字段
-                                    total_input_tokens += usage.input_tokens.unwrap_or(0)
-                                        + usage.cache_creation_input_tokens.unwrap_or(0)
-                                        + usage.cache_read_input_tokens.unwrap_or(0);
-                                    total_output_tokens += usage.output_tokens.unwrap_or(0);
-                                },
-                                "gpt-4" | "gpt-3.5-turbo" => {
-                                    // GPT模型使用标准字段
-                                    total_input_tokens += usage.input_tokens.unwrap_or(0);
-                                    total_output_tokens += usage.output_tokens.unwrap_or(0);
-                                },
-                                _ => {
-                                    // 默认使用Claude的统计方式
-                                    total_input_tokens += usage.input_tokens.unwrap_or(0)
-                                        + usage.cache_creation_input_tokens.unwrap_or(0)
-                                        + usage.cache_read_input_tokens.unwrap_or(0);
-                                    total_output_tokens += usage.output_tokens.unwrap_or(0);
-                                }
-                            }
-                        }
-                    }
+            let Some(message) = &entry.message else {
+                continue;
+            };
+            if let Some(usage) = &message.usage {
+                let model = message.model.as_deref().unwrap_or(default_model);
+                let cost = message_cost(usage, model);
+                let session_cost = session_cost.get_or_insert_with(SessionCost::default);
+                session_cost.total += cost;
+                if entry.is_sidechain {
+                    session_cost.subagents += cost;
                 }
             }
         }
 
-        (total_input_tokens, total_output_tokens)
+        session_cost
     }
 }
 
 impl Segment for CostSegment {
     fn render(&self, input: &InputData) -> String {
-        if !self.enabled {
-            return String::new();
-        }
-        
-        let model_name = Self::extract_model_name(&input.model.display_name);
-        let (input_tokens, output_tokens) = self.parse_transcript_costs(&input.transcript_path, model_name);
-        
-        if input_tokens == 0 && output_tokens == 0 {
-            return String::from("\u{f09d3} $0.000");
-        }
-        
-        let total_cost = self.calculate_total_cost(input_tokens, output_tokens, model_name);
-        
-        // 根据费用大小选择显示单位
-        let cost_display = if total_cost < 0.01 {
-            format!("${:.4}", total_cost)
-        } else if total_cost < 1.0 {
-            format!("${:.3}", total_cost)
-        } else {
-            format!("${:.2}", total_cost)
-        };
-        
-        format!("\u{f09d3} {}", cost_display)
+        self.render_with_severity(input).0
     }
-    
+
     fn enabled(&self) -> bool {
         self.enabled
     }
-}
\ No newline at end of file
+}
+
+/// Compare `(name, spent, limit)` triples against their limits; the worst one wins.
+fn evaluate_budgets(spend: &[(&'static str, f64, f64)], warning_threshold: f64) -> BudgetStatus {
+    let mut status = BudgetStatus {
+        severity: Severity::Normal,
+        exceeded: Vec::new(),
+    };
+
+    for &(name, spent, limit) in spend {
+        if limit <= 0.0 {
+            continue;
+        }
+        let used = spent / limit;
+        if used >= 1.0 {
+            status.severity = Severity::Critical;
+            status.exceeded.push(name);
+        } else if used >= warning_threshold {
+            status.severity = status.severity.max(Severity::Warning);
+        }
+    }
+
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::transcript::TestTranscript;
+
+    #[test]
+    fn test_budget_levels() {
+        assert_eq!(evaluate_budgets(&[], 0.5).severity, Severity::Normal);
+        assert_eq!(
+            evaluate_budgets(&[("session", 2.0, 5.0)], 0.5).severity,
+            Severity::Normal
+        );
+        assert_eq!(
+            evaluate_budgets(&[("session", 2.5, 5.0), ("daily", 1.0, 20.0)], 0.5).severity,
+            Severity::Warning
+        );
+
+        let status = evaluate_budgets(
+            &[
+                ("session", 6.0, 5.0),
+                ("daily", 15.0, 20.0),
+                ("monthly", 320.0, 300.0),
+            ],
+            0.8,
+        );
+        assert_eq!(status.severity, Severity::Critical);
+        assert_eq!(status.exceeded, ["session", "monthly"]);
+    }
+
+    #[test]
+    fn test_streamed_response_counted_once() {
+        let line = |id: &str, output: u32, sidechain: bool| {
+            format!(
+                r#"{{"type":"assistant","requestId":"req_{id}","isSidechain":{sidechain},"message":{{"id":"msg_{id}","type":"message","role":"assistant","model":"claude-sonnet-4","usage":{{"input_tokens":1000,"output_tokens":{output}}}}}}}"#
+            )
+        };
+        // One response streamed over three lines, then a sub-agent response
+        let lines = [
+            line("a", 1, false),
+            line("a", 1, false),
+            line("a", 400, false),
+            line("b", 100, true),
+        ];
+        let transcript = TestTranscript::new(&lines);
+        let cost =
+            CostSegment::new(true).parse_transcript_cost(&transcript.path, "claude-sonnet-4");
+
+        let cost = cost.unwrap();
+        let expected_main = calculate_cost(1000, 400, pricing_model("claude-sonnet-4"));
+        let expected_agent = calculate_cost(1000, 100, pricing_model("claude-sonnet-4"));
+        assert!((cost.total - expected_main - expected_agent).abs() < 1e-9);
+        assert!((cost.subagents - expected_agent).abs() < 1e-9);
+    }
+}
@@ -1,25 +1,122 @@
-use super::Segment;
-use crate::config::InputData;
-use std::path::Path;
+use super::git::repo_root;
+use super::{Segment, Severity};
+use crate::config::{DirectoryConfig, DirectoryStyle, InputData};
+use std::path::{Component, Path, PathBuf};
 
 pub struct DirectorySegment {
     enabled: bool,
+    style: DirectoryStyle,
+    components: usize,
+    max_length: Option<usize>,
+    show_project: bool,
 }
 
 impl DirectorySegment {
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
+        Self {
+            enabled,
+            style: DirectoryStyle::Basename,
+            components: 2,
+            max_length: None,
+            show_project: true,
+        }
     }
-}
 
-impl Segment for DirectorySegment {
-    fn render(&self, input: &InputData) -> String {
+    pub fn from_config(config: &DirectoryConfig) -> Self {
+        Self::new(config.enabled)
+            .with_style(config.style, config.components)
+            .with_max_length(config.max_length)
+            .with_project(config.show_project)
+    }
+
+    /// `components` is how many trailing components `DirectoryStyle::Last` keeps.
+    pub fn with_style(mut self, style: DirectoryStyle, components: usize) -> Self {
+        self.style = style;
+        self.components = components;
+        self
+    }
+
+    pub fn with_max_length(mut self, max_length: Option<usize>) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    pub fn with_project(mut self, show_project: bool) -> Self {
+        self.show_project = show_project;
+        self
+    }
+
+    /// Render the segment; a current directory outside the project is a warning.
+    pub fn render_with_severity(&self, input: &InputData) -> (String, Severity) {
         if !self.enabled {
-            return String::new();
+            return (String::new(), Severity::Normal);
         }
 
-        let dir_name = get_current_dir_name(&input.workspace.current_dir);
-        format!("\u{f024b} {}", dir_name)
+        let current_dir = Path::new(&input.workspace.current_dir);
+        let project_dir = Path::new(&input.workspace.project_dir);
+        let in_project = if input.workspace.project_dir.is_empty() {
+            None
+        } else {
+            relative_path(current_dir, project_dir)
+        };
+
+        let dir_name = match &in_project {
+            Some(sub_path) if self.show_project && !sub_path.as_os_str().is_empty() => {
+                self.format_project_path(project_dir, sub_path)
+            }
+            _ => {
+                let root = match self.style {
+                    DirectoryStyle::Repo => repo_root(&input.workspace.current_dir),
+                    _ => None,
+                };
+                let home = dirs::home_dir();
+                self.format_path(current_dir, home.as_deref(), root.as_deref())
+            }
+        };
+
+        let severity = if in_project.is_none() && !input.workspace.project_dir.is_empty() {
+            Severity::Warning
+        } else {
+            Severity::Normal
+        };
+        (format!("\u{f024b} {}", dir_name), severity)
+    }
+
+    /// `project › sub/path`. The sub path is shown in full unless `fish` or `last` shorten it,
+    /// and only the sub path is truncated so the project name stays.
+    fn format_project_path(&self, project_dir: &Path, sub_path: &Path) -> String {
+        let project = get_current_dir_name(project_dir);
+        let joined = join_components(sub_path);
+        let sub_path = match self.style {
+            DirectoryStyle::Basename | DirectoryStyle::Full | DirectoryStyle::Repo => joined,
+            DirectoryStyle::Fish => fish_path(&joined),
+            DirectoryStyle::Last => last_components(&joined, self.components),
+        };
+        let budget = self
+            .max_length
+            .map(|max| max.saturating_sub(project.chars().count() + 3).max(2));
+        format!("{} \u{203a} {}", project, truncate_start(&sub_path, budget))
+    }
+
+    fn format_path(&self, path: &Path, home: Option<&Path>, root: Option<&Path>) -> String {
+        let formatted = match self.style {
+            DirectoryStyle::Basename => get_current_dir_name(path),
+            DirectoryStyle::Full => tilde_path(path, home),
+            DirectoryStyle::Fish => fish_path(&tilde_path(path, home)),
+            DirectoryStyle::Repo => match root.and_then(|root| relative_to_repo(path, root)) {
+                Some(relative) => relative,
+                // Outside a repository there is no root to start from
+                None => tilde_path(path, home),
+            },
+            DirectoryStyle::Last => last_components(&tilde_path(path, home), self.components),
+        };
+        truncate_start(&formatted, self.max_length)
+    }
+}
+
+impl Segment for DirectorySegment {
+    fn render(&self, input: &InputData) -> String {
+        self.render_with_severity(input).0
     }
 
     fn enabled(&self) -> bool {
@@ -34,3 +131,195 @@ fn get_current_dir_name<P: AsRef<Path>>(path: P) -> String {
         .unwrap_or("unknown")
         .to_string()
 }
+
+/// `path` with `/`-separated components and the home directory shown as `~`.
+fn tilde_path(path: &Path, home: Option<&Path>) -> String {
+    let (prefix, rest) = match home.and_then(|home| path.strip_prefix(home).ok()) {
+        Some(rest) => ("~", rest),
+        None => ("", path),
+    };
+    let mut parts = vec![prefix.to_string()];
+    for component in rest.components() {
+        match component {
+            Component::RootDir => {}
+            Component::Prefix(prefix) => parts[0] = prefix.as_os_str().to_string_lossy().into(),
+            other => parts.push(other.as_os_str().to_string_lossy().into_owned()),
+        }
+    }
+    if parts.len() == 1 && parts[0].is_empty() {
+        return "/".to_string();
+    }
+    parts.join("/")
+}
+
+/// Shorten every component but the last to its first character, keeping a leading dot.
+fn fish_path(path: &str) -> String {
+    let parts: Vec<&str> = path.split('/').collect();
+    let last = parts.len() - 1;
+    parts
+        .iter()
+        .enumerate()
+        .map(|(i, part)| {
+            if i == last || *part == "~" {
+                return part.to_string();
+            }
+            let take = if part.starts_with('.') { 2 } else { 1 };
+            part.chars().take(take).collect()
+        })
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+/// The last `components` components of a `/`-separated path, or all of it if it is shorter.
+fn last_components(path: &str, components: usize) -> String {
+    let parts: Vec<&str> = path.split('/').filter(|part| !part.is_empty()).collect();
+    if parts.len() > components {
+        parts[parts.len() - components.max(1)..].join("/")
+    } else {
+        path.to_string()
+    }
+}
+
+/// `repo/sub/dir` for a `path` inside the repository at `root`.
+fn relative_to_repo(path: &Path, root: &Path) -> Option<String> {
+    let relative = relative_path(path, root)?;
+    let name = get_current_dir_name(root);
+    if relative.as_os_str().is_empty() {
+        return Some(name);
+    }
+    Some(format!("{}/{}", name, join_components(&relative)))
+}
+
+/// `path` relative to `base`, or `None` when it is outside.
+fn relative_path(path: &Path, base: &Path) -> Option<PathBuf> {
+    if let Ok(relative) = path.strip_prefix(base) {
+        return Some(relative.to_path_buf());
+    }
+    // git and Claude Code may report the same directory with and without symlinks resolved
+    let path = path.canonicalize().ok()?;
+    let base = base.canonicalize().ok()?;
+    path.strip_prefix(base).ok().map(Path::to_path_buf)
+}
+
+fn join_components(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+/// Keep the end of `text`, which names the current directory, and mark the cut with `…`.
+fn truncate_start(text: &str, max_length: Option<usize>) -> String {
+    let length = text.chars().count();
+    match max_length {
+        Some(max) if max > 0 && length > max => {
+            let kept: String = text.chars().skip(length - (max - 1)).collect();
+            format!("…{}", kept)
+        }
+        _ => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(style: DirectoryStyle, path: &str, root: Option<&str>) -> String {
+        DirectorySegment::new(true)
+            .with_style(style, 2)
+            .format_path(
+                Path::new(path),
+                Some(Path::new("/home/me")),
+                root.map(Path::new),
+            )
+    }
+
+    #[test]
+    fn test_directory_styles() {
+        let path = "/home/me/work/projects/crate/src";
+        let root = Some("/home/me/work/projects/crate");
+
+        assert_eq!(format(DirectoryStyle::Basename, path, root), "src");
+        assert_eq!(
+            format(DirectoryStyle::Full, path, root),
+            "~/work/projects/crate/src"
+        );
+        assert_eq!(format(DirectoryStyle::Full, "/opt/app", None), "/opt/app");
+        assert_eq!(format(DirectoryStyle::Fish, path, root), "~/w/p/c/src");
+        assert_eq!(
+            format(DirectoryStyle::Fish, "/home/me/.config/ccline", None),
+            "~/.c/ccline"
+        );
+        assert_eq!(format(DirectoryStyle::Repo, path, root), "crate/src");
+        assert_eq!(format(DirectoryStyle::Repo, "/opt/app", None), "/opt/app");
+        assert_eq!(format(DirectoryStyle::Last, path, root), "crate/src");
+        assert_eq!(format(DirectoryStyle::Last, "/home/me/x", None), "~/x");
+    }
+
+    #[test]
+    fn test_max_length() {
+        let segment = DirectorySegment::new(true)
+            .with_style(DirectoryStyle::Full, 2)
+            .with_max_length(Some(12));
+        let formatted = segment.format_path(
+            Path::new("/home/me/work/projects/crate"),
+            Some(Path::new("/home/me")),
+            None,
+        );
+        assert_eq!(formatted, "…jects/crate");
+        assert_eq!(truncate_start("crate", Some(12)), "crate");
+    }
+
+    #[test]
+    fn test_project_dir() {
+        let input = |current_dir: &str| InputData {
+            workspace: crate::config::Workspace {
+                current_dir: current_dir.to_string(),
+                project_dir: "/work/crate".to_string(),
+            },
+            ..InputData::default()
+        };
+        let segment = DirectorySegment::new(true);
+
+        assert_eq!(
+            segment.render_with_severity(&input("/work/crate/src/core")),
+            (
+                "\u{f024b} crate \u{203a} src/core".to_string(),
+                Severity::Normal
+            )
+        );
+        assert_eq!(
+            segment.render_with_severity(&input("/work/crate")),
+            ("\u{f024b} crate".to_string(), Severity::Normal)
+        );
+        assert_eq!(
+            segment.render_with_severity(&input("/tmp/scratch")),
+            ("\u{f024b} scratch".to_string(), Severity::Warning)
+        );
+
+        // The style applies to the path inside the project
+        let sub_path = |style| {
+            DirectorySegment::new(true)
+                .with_style(style, 2)
+                .format_project_path(Path::new("/work/crate"), Path::new("src/core/segments"))
+        };
+        assert_eq!(
+            sub_path(DirectoryStyle::Full),
+            "crate \u{203a} src/core/segments"
+        );
+        assert_eq!(
+            sub_path(DirectoryStyle::Fish),
+            "crate \u{203a} s/c/segments"
+        );
+        assert_eq!(
+            sub_path(DirectoryStyle::Last),
+            "crate \u{203a} core/segments"
+        );
+
+        let truncated = DirectorySegment::new(true)
+            .with_style(DirectoryStyle::Full, 2)
+            .with_max_length(Some(14))
+            .format_project_path(Path::new("/work/crate"), Path::new("src/core/segments"));
+        assert_eq!(truncated, "crate \u{203a} …ments");
+    }
+}
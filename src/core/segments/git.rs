@@ -1,8 +1,12 @@
 use super::Segment;
-use crate::config::InputData;
-use std::process::Command;
+use crate::config::{GitConfig, GitSymbols, InputData};
+use crate::core::trace;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::PathBuf;
+use std::process::{Command, Output};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitInfo {
     pub branch: String,
     pub status: GitStatus,
@@ -11,16 +15,30 @@ pub struct GitInfo {
     pub sha: Option<String>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum GitStatus {
     Clean,
     Dirty,
     Conflicts,
 }
 
+/// Where the git segment gets the repository state from.
+#[derive(Debug, Clone, Default)]
+pub enum GitSource {
+    /// Run git in the workspace directory
+    #[default]
+    Live,
+    /// State recorded earlier, `None` outside a repository (see `ccline replay`)
+    Snapshot(Option<GitInfo>),
+}
+
 pub struct GitSegment {
     enabled: bool,
     show_sha: bool,
+    show_ahead_behind: bool,
+    max_branch_length: Option<usize>,
+    symbols: GitSymbols,
+    source: GitSource,
 }
 
 impl GitSegment {
@@ -28,15 +46,48 @@ impl GitSegment {
         Self {
             enabled,
             show_sha: false,
+            show_ahead_behind: true,
+            max_branch_length: None,
+            symbols: GitSymbols::default(),
+            source: GitSource::Live,
         }
     }
 
+    pub fn from_config(config: &GitConfig) -> Self {
+        Self::new(config.enabled)
+            .with_sha(config.show_sha)
+            .with_ahead_behind(config.show_ahead_behind)
+            .with_max_branch_length(config.max_branch_length)
+            .with_symbols(config.symbols.clone())
+    }
+
     pub fn with_sha(mut self, show_sha: bool) -> Self {
         self.show_sha = show_sha;
         self
     }
 
-    fn get_git_info(&self, working_dir: &str) -> Option<GitInfo> {
+    pub fn with_ahead_behind(mut self, show_ahead_behind: bool) -> Self {
+        self.show_ahead_behind = show_ahead_behind;
+        self
+    }
+
+    pub fn with_max_branch_length(mut self, max_branch_length: Option<usize>) -> Self {
+        self.max_branch_length = max_branch_length;
+        self
+    }
+
+    pub fn with_symbols(mut self, symbols: GitSymbols) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    pub fn with_source(mut self, source: GitSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Repository state of `working_dir`, or `None` when it isn't inside a repository.
+    pub fn get_git_info(&self, working_dir: &str) -> Option<GitInfo> {
         // First check if we're in a Git repository
         if !self.is_git_repository(working_dir) {
             return None;
@@ -46,7 +97,11 @@ impl GitSegment {
             .get_branch(working_dir)
             .unwrap_or_else(|| "detached".to_string());
         let status = self.get_status(working_dir);
-        let (ahead, behind) = self.get_ahead_behind(working_dir);
+        let (ahead, behind) = if self.show_ahead_behind {
+            self.get_ahead_behind(working_dir)
+        } else {
+            (0, 0)
+        };
         let sha = if self.show_sha {
             self.get_sha(working_dir)
         } else {
@@ -63,20 +118,13 @@ impl GitSegment {
     }
 
     fn is_git_repository(&self, working_dir: &str) -> bool {
-        Command::new("git")
-            .args(["rev-parse", "--git-dir"])
-            .current_dir(working_dir)
-            .output()
+        run_git(working_dir, &["rev-parse", "--git-dir"])
             .map(|output| output.status.success())
             .unwrap_or(false)
     }
 
     fn get_branch(&self, working_dir: &str) -> Option<String> {
-        let output = Command::new("git")
-            .args(["branch", "--show-current"])
-            .current_dir(working_dir)
-            .output()
-            .ok()?;
+        let output = run_git(working_dir, &["branch", "--show-current"]).ok()?;
 
         if output.status.success() {
             let branch = String::from_utf8(output.stdout).ok()?.trim().to_string();
@@ -91,10 +139,7 @@ impl GitSegment {
     }
 
     fn get_status(&self, working_dir: &str) -> GitStatus {
-        let output = Command::new("git")
-            .args(["status", "--porcelain"])
-            .current_dir(working_dir)
-            .output();
+        let output = run_git(working_dir, &["status", "--porcelain"]);
 
         match output {
             Ok(output) if output.status.success() => {
@@ -125,10 +170,7 @@ impl GitSegment {
     }
 
     fn get_commit_count(&self, working_dir: &str, range: &str) -> u32 {
-        let output = Command::new("git")
-            .args(["rev-list", "--count", range])
-            .current_dir(working_dir)
-            .output();
+        let output = run_git(working_dir, &["rev-list", "--count", range]);
 
         match output {
             Ok(output) if output.status.success() => String::from_utf8(output.stdout)
@@ -140,11 +182,7 @@ impl GitSegment {
     }
 
     fn get_sha(&self, working_dir: &str) -> Option<String> {
-        let output = Command::new("git")
-            .args(["rev-parse", "--short=7", "HEAD"])
-            .current_dir(working_dir)
-            .output()
-            .ok()?;
+        let output = run_git(working_dir, &["rev-parse", "--short=7", "HEAD"]).ok()?;
 
         if output.status.success() {
             let sha = String::from_utf8(output.stdout).ok()?.trim().to_string();
@@ -159,24 +197,34 @@ impl GitSegment {
     }
 
     fn format_git_status(&self, info: &GitInfo) -> String {
+        let symbols = &self.symbols;
+        let symbol = |custom: &Option<String>, default: &'static str| {
+            custom.clone().unwrap_or_else(|| default.to_string())
+        };
         let mut parts = Vec::new();
 
         // Branch name with Nerd Font branch icon
-        parts.push(format!("\u{f02a2} {}", info.branch));
+        parts.push(format!(
+            "{} {}",
+            symbol(&symbols.branch, "\u{f02a2}"),
+            truncate_branch(&info.branch, self.max_branch_length)
+        ));
 
         // Status indicators using simple Unicode symbols
         match info.status {
-            GitStatus::Clean => parts.push("✓".to_string()),
-            GitStatus::Dirty => parts.push("●".to_string()),
-            GitStatus::Conflicts => parts.push("⚠".to_string()),
+            GitStatus::Clean => parts.push(symbol(&symbols.clean, "✓")),
+            GitStatus::Dirty => parts.push(symbol(&symbols.dirty, "●")),
+            GitStatus::Conflicts => parts.push(symbol(&symbols.conflicts, "⚠")),
         }
 
         // Remote tracking status with arrows
-        if info.ahead > 0 {
-            parts.push(format!("↑{}", info.ahead));
-        }
-        if info.behind > 0 {
-            parts.push(format!("↓{}", info.behind));
+        if self.show_ahead_behind {
+            if info.ahead > 0 {
+                parts.push(format!("{}{}", symbol(&symbols.ahead, "↑"), info.ahead));
+            }
+            if info.behind > 0 {
+                parts.push(format!("{}{}", symbol(&symbols.behind, "↓"), info.behind));
+            }
         }
 
         // Short SHA hash
@@ -188,13 +236,62 @@ impl GitSegment {
     }
 }
 
+/// Cut `branch` to `max` characters, ending in `…`.
+fn truncate_branch(branch: &str, max: Option<usize>) -> String {
+    match max {
+        Some(max) if max > 0 && branch.chars().count() > max => {
+            let kept: String = branch.chars().take(max - 1).collect();
+            format!("{}…", kept)
+        }
+        _ => branch.to_string(),
+    }
+}
+
+/// Top-level directory of the repository containing `working_dir`.
+pub fn repo_root(working_dir: &str) -> Option<PathBuf> {
+    let output = run_git(working_dir, &["rev-parse", "--show-toplevel"]).ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let root = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!root.is_empty()).then(|| PathBuf::from(root))
+}
+
+/// Run `git` in `working_dir`, tracing the command, its exit status and duration.
+fn run_git(working_dir: &str, args: &[&str]) -> io::Result<Output> {
+    trace::timed(
+        "git",
+        || {
+            Command::new("git")
+                .args(args)
+                .current_dir(working_dir)
+                .output()
+        },
+        |result| {
+            let outcome = match result {
+                Ok(output) => output.status.to_string(),
+                Err(e) => e.to_string(),
+            };
+            format!("git {} → {}", args.join(" "), outcome)
+        },
+    )
+}
+
 impl Segment for GitSegment {
     fn render(&self, input: &InputData) -> String {
         if !self.enabled {
             return String::new();
         }
 
-        match self.get_git_info(&input.workspace.current_dir) {
+        let git_info = match &self.source {
+            GitSource::Live => self.get_git_info(&input.workspace.current_dir),
+            GitSource::Snapshot(snapshot) => snapshot.clone().map(|info| GitInfo {
+                sha: info.sha.filter(|_| self.show_sha),
+                ..info
+            }),
+        };
+
+        match git_info {
             Some(git_info) => self.format_git_status(&git_info),
             None => String::new(), // Not in a Git repository
         }
@@ -204,3 +301,40 @@ impl Segment for GitSegment {
         self.enabled
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_with_options() {
+        let info = GitInfo {
+            branch: "feature/long-branch-name".to_string(),
+            status: GitStatus::Dirty,
+            ahead: 2,
+            behind: 1,
+            sha: Some("abc1234".to_string()),
+        };
+        let config = GitConfig {
+            show_ahead_behind: false,
+            max_branch_length: Some(10),
+            symbols: GitSymbols {
+                branch: Some("git:".to_string()),
+                dirty: Some("*".to_string()),
+                ..GitSymbols::default()
+            },
+            ..GitConfig::default()
+        };
+        let segment = GitSegment::from_config(&config);
+        assert_eq!(
+            segment.format_git_status(&info),
+            "git: feature/l… * abc1234"
+        );
+
+        let segment = GitSegment::new(true);
+        assert_eq!(
+            segment.format_git_status(&info),
+            "\u{f02a2} feature/long-branch-name ● ↑2 ↓1 abc1234"
+        );
+    }
+}
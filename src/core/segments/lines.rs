@@ -0,0 +1,157 @@
+use super::Segment;
+use crate::config::{InputData, LinesConfig};
+use crate::core::transcript;
+use serde_json::Value;
+use std::path::Path;
+
+pub struct LinesSegment {
+    enabled: bool,
+}
+
+impl LinesSegment {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    pub fn from_config(config: &LinesConfig) -> Self {
+        Self::new(config.enabled)
+    }
+
+    /// Lines added/removed this session, preferring the totals Claude Code reports on stdin.
+    pub fn get_line_counts(&self, input: &InputData) -> (u32, u32) {
+        if let Some(cost) = &input.cost {
+            if let (Some(added), Some(removed)) = (cost.total_lines_added, cost.total_lines_removed)
+            {
+                return (added, removed);
+            }
+        }
+
+        parse_transcript_line_changes(&input.transcript_path)
+    }
+}
+
+impl Segment for LinesSegment {
+    fn render(&self, input: &InputData) -> String {
+        if !self.enabled {
+            return String::new();
+        }
+
+        let (added, removed) = self.get_line_counts(input);
+        if added == 0 && removed == 0 {
+            return String::new();
+        }
+
+        format!("\u{f440} +{} \u{2212}{}", added, removed)
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+/// Sum the line changes of every Edit/MultiEdit/Write tool call in the transcript.
+fn parse_transcript_line_changes<P: AsRef<Path>>(transcript_path: P) -> (u32, u32) {
+    let mut added = 0;
+    let mut removed = 0;
+
+    for entry in transcript::read_entries(transcript_path) {
+        if entry.entry_type != "assistant" {
+            continue;
+        }
+        let Some(message) = entry.message else {
+            continue;
+        };
+
+        for block in message.content {
+            if block.content_type != "tool_use" {
+                continue;
+            }
+            if let (Some(name), Some(tool_input)) = (&block.name, &block.input) {
+                let (a, r) = count_tool_line_changes(name, tool_input);
+                added += a;
+                removed += r;
+            }
+        }
+    }
+
+    (added, removed)
+}
+
+fn count_tool_line_changes(tool_name: &str, tool_input: &Value) -> (u32, u32) {
+    match tool_name {
+        "Edit" => count_edit(tool_input),
+        "MultiEdit" => tool_input
+            .get("edits")
+            .and_then(Value::as_array)
+            .map(|edits| {
+                edits
+                    .iter()
+                    .map(count_edit)
+                    .fold((0, 0), |(a, r), (ea, er)| (a + ea, r + er))
+            })
+            .unwrap_or((0, 0)),
+        // Write replaces the whole file; without the previous contents every line counts as added
+        "Write" => (count_lines(string_field(tool_input, "content")), 0),
+        _ => (0, 0),
+    }
+}
+
+fn count_edit(edit: &Value) -> (u32, u32) {
+    let old_lines: Vec<&str> = string_field(edit, "old_string").lines().collect();
+    let new_lines: Vec<&str> = string_field(edit, "new_string").lines().collect();
+
+    // Lines shared at the start or end of both strings are context, not changes
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(old, new)| old == new)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+
+    let added = new_lines.len() - prefix - suffix;
+    let removed = old_lines.len() - prefix - suffix;
+    (added as u32, removed as u32)
+}
+
+fn string_field<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(Value::as_str).unwrap_or("")
+}
+
+fn count_lines(text: &str) -> u32 {
+    text.lines().count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_edit_ignores_unchanged_context() {
+        let edit = json!({
+            "old_string": "fn main() {\n    old();\n}",
+            "new_string": "fn main() {\n    new();\n    more();\n}",
+        });
+        assert_eq!(count_tool_line_changes("Edit", &edit), (2, 1));
+    }
+
+    #[test]
+    fn test_multi_edit_and_write() {
+        let multi = json!({
+            "edits": [
+                { "old_string": "a", "new_string": "b" },
+                { "old_string": "c\nd", "new_string": "" },
+            ]
+        });
+        assert_eq!(count_tool_line_changes("MultiEdit", &multi), (1, 3));
+
+        let write = json!({ "content": "one\ntwo\nthree\n" });
+        assert_eq!(count_tool_line_changes("Write", &write), (3, 0));
+        assert_eq!(count_tool_line_changes("Read", &write), (0, 0));
+    }
+}
@@ -1,9 +1,15 @@
+pub mod block;
 pub mod cost;
 pub mod directory;
 pub mod git;
+pub mod lines;
 pub mod model;
+pub mod output_style;
+pub mod session;
+pub mod spend;
 pub mod time;
 pub mod usage;
+pub mod version;
 
 use crate::config::InputData;
 
@@ -12,10 +18,24 @@ pub trait Segment {
     fn enabled(&self) -> bool;
 }
 
+/// How close a segment's value is to its configured limit; the statusline picks colors from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Normal,
+    Warning,
+    Critical,
+}
+
 // Re-export all segment types
+pub use block::BlockSegment;
 pub use cost::CostSegment;
 pub use directory::DirectorySegment;
 pub use git::GitSegment;
+pub use lines::LinesSegment;
 pub use model::ModelSegment;
+pub use output_style::OutputStyleSegment;
+pub use session::SessionSegment;
+pub use spend::SpendSegment;
 pub use time::TimeSegment;
 pub use usage::UsageSegment;
+pub use version::VersionSegment;
@@ -1,13 +1,101 @@
 use super::Segment;
-use crate::config::InputData;
+use crate::config::{InputData, ModelAlias, ModelConfig};
+
+/// Used when `segments.model.format` is unset.
+const DEFAULT_FORMAT: &str = "{tier} {version} {context}";
 
 pub struct ModelSegment {
     enabled: bool,
+    format: Option<String>,
+    aliases: Vec<ModelAlias>,
+}
+
+/// The parts of a Claude model id such as `claude-opus-4-1-20250805[1m]`.
+#[derive(Debug, Default, PartialEq)]
+pub struct ModelId {
+    /// `Claude`
+    pub family: String,
+    /// `Opus`, `Sonnet`, `Haiku`, or whatever a new model is called
+    pub tier: String,
+    /// `4.1`; empty when the id has no version
+    pub version: String,
+    /// Release date as in the id, `20250805`
+    pub date: String,
+    /// Context window variant from a `[1m]` suffix, `1M`
+    pub context: String,
+}
+
+impl ModelId {
+    /// Parse both `claude-<tier>-<version>` and the older `claude-<version>-<tier>` ids,
+    /// including Bedrock (`anthropic.claude-…-v1:0`) and Vertex (`claude-…@20240620`) forms.
+    pub fn parse(id: &str) -> Option<Self> {
+        let (base, context) = match id.trim().strip_suffix(']') {
+            Some(rest) => {
+                let (base, context) = rest.rsplit_once('[')?;
+                (base, context.to_uppercase())
+            }
+            None => (id.trim(), String::new()),
+        };
+        let base = base.to_lowercase();
+        let start = base.find("claude")?;
+        let (name, vertex_date) = match base[start..].split_once('@') {
+            Some((name, date)) => (name, date),
+            None => (&base[start..], ""),
+        };
+
+        let mut tokens = name.split('-');
+        let family = capitalize(tokens.next()?);
+        let mut tier = String::new();
+        let mut version = Vec::new();
+        let mut date = vertex_date.to_string();
+        for token in tokens {
+            let numeric = token.chars().all(|c| c.is_ascii_digit());
+            if numeric && token.len() == 8 {
+                date = token.to_string();
+            } else if numeric && !token.is_empty() && token.len() <= 2 {
+                version.push(token);
+            } else if tier.is_empty() && token.chars().all(|c| c.is_ascii_alphabetic()) {
+                tier = capitalize(token);
+            }
+            // Anything else, like Bedrock's `v1:0` or `latest`, isn't part of the name
+        }
+        if tier.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            family,
+            tier,
+            version: version.join("."),
+            date,
+            context,
+        })
+    }
 }
 
 impl ModelSegment {
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
+        Self {
+            enabled,
+            format: None,
+            aliases: Vec::new(),
+        }
+    }
+
+    pub fn from_config(config: &ModelConfig) -> Self {
+        Self::new(config.enabled)
+            .with_format(config.format.clone())
+            .with_aliases(config.aliases.clone())
+    }
+
+    pub fn with_format(mut self, format: Option<String>) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_aliases(mut self, aliases: Vec<ModelAlias>) -> Self {
+        self.aliases = aliases;
+        self
     }
 }
 
@@ -19,7 +107,7 @@ impl Segment for ModelSegment {
 
         format!(
             "\u{e26d} {}",
-            self.format_model_name(&input.model.display_name)
+            self.format_model_name(&input.model.id, &input.model.display_name)
         )
     }
 
@@ -29,17 +117,146 @@ impl Segment for ModelSegment {
 }
 
 impl ModelSegment {
-    fn format_model_name(&self, display_name: &str) -> String {
-        // Simplify model display names
-        match display_name {
-            name if name.contains("claude-3-5-sonnet") => "Sonnet 3.5".to_string(),
-            name if name.contains("claude-3-7-sonnet") => "Sonnet 3.7".to_string(),
-            name if name.contains("claude-3-sonnet") => "Sonnet 3".to_string(),
-            name if name.contains("claude-3-haiku") => "Haiku 3".to_string(),
-            name if name.contains("claude-4-sonnet") => "Sonnet 4".to_string(),
-            name if name.contains("claude-4-opus") => "Opus 4".to_string(),
-            name if name.contains("sonnet-4") => "Sonnet 4".to_string(),
-            _ => display_name.to_string(),
+    fn format_model_name(&self, id: &str, display_name: &str) -> String {
+        // Older payloads only have a display name, which used to be the id
+        let id = if id.is_empty() { display_name } else { id };
+        let display_name = if display_name.is_empty() {
+            id
+        } else {
+            display_name
+        };
+        let parsed = ModelId::parse(id);
+
+        let alias = self
+            .aliases
+            .iter()
+            .find(|alias| wildcard_match(&alias.pattern.to_lowercase(), &id.to_lowercase()));
+        let name = match (alias, &parsed) {
+            (Some(alias), _) => fill_template(&alias.name, id, display_name, parsed.as_ref()),
+            (None, Some(parsed)) => fill_template(
+                self.format.as_deref().unwrap_or(DEFAULT_FORMAT),
+                id,
+                display_name,
+                Some(parsed),
+            ),
+            (None, None) => String::new(),
+        };
+
+        if name.is_empty() {
+            display_name.to_string()
+        } else {
+            name
         }
     }
 }
+
+/// Replace the placeholders and collapse the spaces left by empty ones.
+fn fill_template(template: &str, id: &str, display_name: &str, parsed: Option<&ModelId>) -> String {
+    let empty = ModelId::default();
+    let parsed = parsed.unwrap_or(&empty);
+    template
+        .replace("{family}", &parsed.family)
+        .replace("{tier}", &parsed.tier)
+        .replace("{version}", &parsed.version)
+        .replace("{date}", &parsed.date)
+        .replace("{context}", &parsed.context)
+        .replace("{id}", id)
+        .replace("{display_name}", display_name)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whole-string match where `*` stands for any run of characters.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_model_id() {
+        let opus = ModelId::parse("claude-opus-4-1-20250805").unwrap();
+        assert_eq!(
+            opus,
+            ModelId {
+                family: "Claude".to_string(),
+                tier: "Opus".to_string(),
+                version: "4.1".to_string(),
+                date: "20250805".to_string(),
+                context: String::new(),
+            }
+        );
+
+        let sonnet = ModelId::parse("claude-sonnet-4-20250514[1m]").unwrap();
+        assert_eq!(
+            (sonnet.version.as_str(), sonnet.context.as_str()),
+            ("4", "1M")
+        );
+        let old = ModelId::parse("anthropic.claude-3-5-sonnet-20240620-v1:0").unwrap();
+        assert_eq!((old.tier.as_str(), old.version.as_str()), ("Sonnet", "3.5"));
+        let vertex = ModelId::parse("claude-3-haiku@20240307").unwrap();
+        assert_eq!(vertex.date, "20240307");
+        assert!(ModelId::parse("glm-4.5").is_none());
+    }
+
+    #[test]
+    fn test_format_model_name() {
+        let segment = ModelSegment::new(true);
+        assert_eq!(
+            segment.format_model_name("claude-sonnet-4-5-20250929", "Sonnet 4.5"),
+            "Sonnet 4.5"
+        );
+        assert_eq!(
+            segment.format_model_name("claude-sonnet-4-20250514[1m]", ""),
+            "Sonnet 4 1M"
+        );
+        assert_eq!(
+            segment.format_model_name("", "claude-3-5-sonnet"),
+            "Sonnet 3.5"
+        );
+        assert_eq!(segment.format_model_name("glm-4.5", "GLM 4.5"), "GLM 4.5");
+
+        let segment = ModelSegment::new(true)
+            .with_format(Some("{family} {tier} {version} ({date})".to_string()))
+            .with_aliases(vec![ModelAlias {
+                pattern: "Claude-Opus-*".to_string(),
+                name: "O{version}".to_string(),
+            }]);
+        assert_eq!(
+            segment.format_model_name("claude-opus-4-1-20250805", "Opus 4.1"),
+            "O4.1"
+        );
+        assert_eq!(
+            segment.format_model_name("claude-haiku-4-5-20251001", ""),
+            "Claude Haiku 4.5 (20251001)"
+        );
+        assert!(wildcard_match("claude-*-4*", "claude-opus-4-1"));
+        assert!(!wildcard_match("claude-*-4", "claude-opus-4-1"));
+    }
+}
@@ -0,0 +1,58 @@
+use super::Segment;
+use crate::config::{InputData, OutputStyleConfig};
+
+/// The output style Claude Code is running with, e.g. `Explanatory` or `Learning`.
+pub struct OutputStyleSegment {
+    enabled: bool,
+}
+
+impl OutputStyleSegment {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    pub fn from_config(config: &OutputStyleConfig) -> Self {
+        Self::new(config.enabled)
+    }
+}
+
+impl Segment for OutputStyleSegment {
+    fn render(&self, input: &InputData) -> String {
+        if !self.enabled || input.output_style.name.is_empty() {
+            return String::new();
+        }
+
+        format!("\u{f1fc} {}", input.output_style.name)
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OutputStyle;
+
+    #[test]
+    fn test_output_style_name() {
+        let input = |name: &str| InputData {
+            output_style: OutputStyle {
+                name: name.to_string(),
+            },
+            ..InputData::default()
+        };
+        let segment = OutputStyleSegment::new(true);
+
+        assert_eq!(segment.render(&input("")), "");
+        assert_eq!(
+            segment.render(&input("Explanatory")),
+            "\u{f1fc} Explanatory"
+        );
+        assert_eq!(
+            OutputStyleSegment::new(false).render(&input("Learning")),
+            ""
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use super::Segment;
+use crate::config::{InputData, SessionConfig};
+use crate::core::transcript::{self, parse_timestamp};
+use chrono::{DateTime, Utc};
+use std::path::Path;
+use std::time::Duration;
+
+pub struct SessionSegment {
+    enabled: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct SessionTimes {
+    /// First to last transcript timestamp
+    pub elapsed: Option<Duration>,
+    /// Time spent waiting on the API, as reported on stdin
+    pub api: Option<Duration>,
+    /// Time since the last assistant message
+    pub idle: Option<Duration>,
+}
+
+impl SessionSegment {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    pub fn from_config(config: &SessionConfig) -> Self {
+        Self::new(config.enabled)
+    }
+
+    pub fn get_session_times(&self, input: &InputData) -> SessionTimes {
+        let mut times = parse_transcript_times(&input.transcript_path, Utc::now());
+        times.api = input
+            .cost
+            .as_ref()
+            .and_then(|cost| cost.total_api_duration_ms)
+            .map(Duration::from_millis);
+        times
+    }
+}
+
+impl Segment for SessionSegment {
+    fn render(&self, input: &InputData) -> String {
+        if !self.enabled {
+            return String::new();
+        }
+
+        let times = self.get_session_times(input);
+        let Some(elapsed) = times.elapsed else {
+            return String::new();
+        };
+
+        let mut parts = vec![format!("\u{f051b} {}", format_duration(elapsed))];
+        if let Some(api) = times.api {
+            parts.push(format!("api {}", format_duration(api)));
+        }
+        // Only worth showing once the session has actually been left alone
+        if let Some(idle) = times.idle.filter(|idle| idle.as_secs() >= 60) {
+            parts.push(format!("idle {}", format_duration(idle)));
+        }
+
+        parts.join(" · ")
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+fn parse_transcript_times<P: AsRef<Path>>(transcript_path: P, now: DateTime<Utc>) -> SessionTimes {
+    let mut first: Option<DateTime<Utc>> = None;
+    let mut last: Option<DateTime<Utc>> = None;
+    let mut last_assistant: Option<DateTime<Utc>> = None;
+
+    for entry in transcript::read_entries(transcript_path) {
+        let Some(timestamp) = entry.timestamp.as_deref().and_then(parse_timestamp) else {
+            continue;
+        };
+
+        first = Some(first.map_or(timestamp, |t| t.min(timestamp)));
+        last = Some(last.map_or(timestamp, |t| t.max(timestamp)));
+        if entry.entry_type == "assistant" {
+            last_assistant = Some(last_assistant.map_or(timestamp, |t| t.max(timestamp)));
+        }
+    }
+
+    SessionTimes {
+        elapsed: first.zip(last).and_then(|(f, l)| (l - f).to_std().ok()),
+        api: None,
+        idle: last_assistant.and_then(|t| (now - t).to_std().ok()),
+    }
+}
+
+/// Compact human-friendly duration: `45s`, `12m`, `1h12m`, `2d3h`.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (days, hours, minutes) = (secs / 86400, (secs / 3600) % 24, (secs / 60) % 60);
+
+    if days > 0 {
+        format!("{}d{}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h{:02}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(45)), "45s");
+        assert_eq!(format_duration(Duration::from_secs(12 * 60 + 5)), "12m");
+        assert_eq!(format_duration(Duration::from_secs(72 * 60)), "1h12m");
+        assert_eq!(format_duration(Duration::from_secs(3600 + 5 * 60)), "1h05m");
+        assert_eq!(format_duration(Duration::from_secs(51 * 3600)), "2d3h");
+    }
+
+    #[test]
+    fn test_transcript_times() {
+        let path = std::env::temp_dir().join("ccline_session_times_test.jsonl");
+        fs::write(
+            &path,
+            concat!(
+                r#"{"type":"user","timestamp":"2025-08-12T09:00:00Z"}"#,
+                "\n",
+                r#"{"type":"assistant","timestamp":"2025-08-12T09:40:00.500Z"}"#,
+                "\n",
+                r#"{"type":"user","timestamp":"2025-08-12T10:12:00Z"}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        let now = parse_timestamp("2025-08-12T10:15:00Z").unwrap();
+        let times = parse_transcript_times(&path, now);
+        fs::remove_file(&path).ok();
+
+        assert_eq!(times.elapsed, Some(Duration::from_secs(72 * 60)));
+        assert_eq!(times.idle.map(|d| d.as_secs()), Some(34 * 60 + 59));
+    }
+}
@@ -0,0 +1,47 @@
+use super::cost::format_cost;
+use super::Segment;
+use crate::config::InputData;
+use crate::core::aggregate::{Period, UsageIndex};
+
+pub struct SpendSegment {
+    enabled: bool,
+    periods: Vec<Period>,
+}
+
+impl SpendSegment {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            periods: vec![Period::Today, Period::Week, Period::Month],
+        }
+    }
+
+    pub fn with_periods(mut self, periods: Vec<Period>) -> Self {
+        self.periods = periods;
+        self
+    }
+}
+
+impl Segment for SpendSegment {
+    fn render(&self, _input: &InputData) -> String {
+        if !self.enabled || self.periods.is_empty() {
+            return String::new();
+        }
+
+        let index = UsageIndex::load();
+        let parts: Vec<String> = self
+            .periods
+            .iter()
+            .map(|&period| {
+                let totals = index.period_totals(period);
+                format!("{} {}", period.label(), format_cost(totals.cost))
+            })
+            .collect();
+
+        format!("\u{f00ed} {}", parts.join(" · "))
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+}
@@ -1,31 +1,117 @@
 use super::Segment;
-use crate::config::InputData;
-use std::time::{SystemTime, UNIX_EPOCH};
+use crate::config::{InputData, TimeConfig};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::fmt::Display;
 
 pub struct TimeSegment {
     enabled: bool,
     format: TimeFormat,
+    timezone: Zone,
+    label: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub enum TimeFormat {
-    HourMinute,    // 14:30
-    HourMinuteSecond, // 14:30:45
-    Timestamp,     // Unix timestamp
+    /// 14:30, 14:30:45, 2:30 PM or 2:30:45 PM
+    Clock { hour12: bool, seconds: bool },
+    /// strftime-style pattern such as `%a %H:%M` or `%s`
+    Custom(String),
+}
+
+#[derive(Debug, Clone)]
+enum Zone {
+    /// System timezone, from `TZ` or `/etc/localtime`
+    Local,
+    Named(Tz),
+    /// A timezone name that isn't in the IANA database
+    Unknown(String),
 }
 
 impl TimeSegment {
     pub fn new(enabled: bool) -> Self {
         Self {
             enabled,
-            format: TimeFormat::HourMinute,
+            format: TimeFormat::Clock {
+                hour12: false,
+                seconds: false,
+            },
+            timezone: Zone::Local,
+            label: None,
         }
     }
-    
+
+    pub fn from_config(config: &TimeConfig) -> Self {
+        let format = match &config.format {
+            Some(pattern) => TimeFormat::Custom(pattern.clone()),
+            None => TimeFormat::Clock {
+                hour12: config.hour12,
+                seconds: config.seconds,
+            },
+        };
+
+        Self::new(config.enabled)
+            .with_format(format)
+            .with_timezone(config.timezone.as_deref())
+            .with_label(config.label.clone())
+    }
+
     pub fn with_format(mut self, format: TimeFormat) -> Self {
         self.format = format;
         self
     }
+
+    /// Use a named IANA timezone instead of the system one.
+    pub fn with_timezone(mut self, timezone: Option<&str>) -> Self {
+        self.timezone = match timezone {
+            None => Zone::Local,
+            Some(name) => name
+                .parse::<Tz>()
+                .map(Zone::Named)
+                .unwrap_or_else(|_| Zone::Unknown(name.to_string())),
+        };
+        self
+    }
+
+    pub fn with_label(mut self, label: Option<String>) -> Self {
+        self.label = label;
+        self
+    }
+
+    fn pattern(&self) -> &str {
+        match &self.format {
+            TimeFormat::Clock {
+                hour12: false,
+                seconds: false,
+            } => "%H:%M",
+            TimeFormat::Clock {
+                hour12: false,
+                seconds: true,
+            } => "%H:%M:%S",
+            TimeFormat::Clock {
+                hour12: true,
+                seconds: false,
+            } => "%-I:%M %p",
+            TimeFormat::Clock {
+                hour12: true,
+                seconds: true,
+            } => "%-I:%M:%S %p",
+            TimeFormat::Custom(pattern) => pattern,
+        }
+    }
+
+    fn format_time<Z: TimeZone>(&self, now: DateTime<Z>) -> String
+    where
+        Z::Offset: Display,
+    {
+        let items: Vec<Item> = StrftimeItems::new(self.pattern()).collect();
+        // chrono panics when displaying an invalid pattern, so fall back to the default clock
+        if items.contains(&Item::Error) {
+            return now.format("%H:%M").to_string();
+        }
+        now.format_with_items(items.into_iter()).to_string()
+    }
 }
 
 impl Segment for TimeSegment {
@@ -33,30 +119,24 @@ impl Segment for TimeSegment {
         if !self.enabled {
             return String::new();
         }
-        
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-            
-        match self.format {
-            TimeFormat::HourMinute => {
-                let hours = (now / 3600) % 24;
-                let minutes = (now / 60) % 60;
-                format!("🕐 {:02}:{:02}", hours, minutes)
-            }
-            TimeFormat::HourMinuteSecond => {
-                let hours = (now / 3600) % 24;
-                let minutes = (now / 60) % 60;
-                let seconds = now % 60;
-                format!("🕐 {:02}:{:02}:{:02}", hours, minutes, seconds)
-            }
-            TimeFormat::Timestamp => {
-                format!("🕐 {}", now)
+
+        let now = Utc::now();
+        let (time, default_label) = match &self.timezone {
+            Zone::Local => (self.format_time(now.with_timezone(&Local)), None),
+            Zone::Named(tz) => {
+                let local = now.with_timezone(tz);
+                let abbreviation = local.format("%Z").to_string();
+                (self.format_time(local), Some(abbreviation))
             }
+            Zone::Unknown(name) => return format!("🕐 {}?", name),
+        };
+
+        match self.label.clone().or(default_label) {
+            Some(label) if !label.is_empty() => format!("🕐 {} {}", time, label),
+            _ => format!("🕐 {}", time),
         }
     }
-    
+
     fn enabled(&self) -> bool {
         self.enabled
     }
@@ -66,14 +146,15 @@ impl Segment for TimeSegment {
 mod tests {
     use super::*;
     use crate::config::{InputData, Model, Workspace};
-    
+    use chrono::NaiveDate;
+
     #[test]
     fn test_time_segment_disabled() {
         let segment = TimeSegment::new(false);
         let input = create_test_input();
         assert_eq!(segment.render(&input), "");
     }
-    
+
     #[test]
     fn test_time_segment_enabled() {
         let segment = TimeSegment::new(true);
@@ -82,16 +163,57 @@ mod tests {
         assert!(result.starts_with("🕐"));
         assert!(result.contains(":"));
     }
-    
+
+    #[test]
+    fn test_time_formats() {
+        let now = NaiveDate::from_ymd_opt(2025, 8, 12)
+            .unwrap()
+            .and_hms_opt(14, 30, 45)
+            .unwrap()
+            .and_utc();
+        let clock = |hour12, seconds| {
+            TimeSegment::new(true).with_format(TimeFormat::Clock { hour12, seconds })
+        };
+
+        assert_eq!(clock(false, false).format_time(now), "14:30");
+        assert_eq!(clock(false, true).format_time(now), "14:30:45");
+        assert_eq!(clock(true, false).format_time(now), "2:30 PM");
+        assert_eq!(
+            TimeSegment::new(true)
+                .with_format(TimeFormat::Custom("%a %H:%M".into()))
+                .format_time(now),
+            "Tue 14:30"
+        );
+        assert_eq!(
+            TimeSegment::new(true)
+                .with_format(TimeFormat::Custom("%Q".into()))
+                .format_time(now),
+            "14:30"
+        );
+    }
+
+    #[test]
+    fn test_named_timezone() {
+        let segment = TimeSegment::new(true).with_timezone(Some("Asia/Tokyo"));
+        let result = segment.render(&create_test_input());
+        assert!(result.ends_with(" JST"));
+
+        let segment = TimeSegment::new(true).with_timezone(Some("Mars/Olympus"));
+        assert_eq!(segment.render(&create_test_input()), "🕐 Mars/Olympus?");
+    }
+
     fn create_test_input() -> InputData {
         InputData {
             model: Model {
                 display_name: "test-model".to_string(),
+                ..Model::default()
             },
             workspace: Workspace {
                 current_dir: "/test".to_string(),
+                ..Workspace::default()
             },
             transcript_path: "/test/transcript.jsonl".to_string(),
+            ..InputData::default()
         }
     }
-}
\ No newline at end of file
+}
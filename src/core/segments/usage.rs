@@ -1,80 +1,40 @@
-use super::Segment;
-use crate::config::{InputData, TranscriptEntry};
-use std::fs;
-use std::io::{BufRead, BufReader};
+use super::{Segment, Severity};
+use crate::config::{InputData, UsageConfig, UsageDisplay};
+use crate::core::transcript;
 use std::path::Path;
 
 const CONTEXT_LIMIT: u32 = 200000;
 
 pub struct UsageSegment {
     enabled: bool,
+    options: UsageConfig,
 }
 
 impl UsageSegment {
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
-    }
-    
-    // 从模型名称中提取基础名称
-    fn extract_model_name(model_display: &str) -> &'static str {
-        if model_display.contains("claude-3-5-sonnet") {
-            "claude-3-5-sonnet-20241022"
-        } else if model_display.contains("haiku") {
-            "claude-3-haiku-20240307"
-        } else if model_display.contains("gpt-4") {
-            "gpt-4"
-        } else if model_display.contains("gpt-3.5") {
-            "gpt-3.5-turbo"
-        } else if model_display.contains("glm-4.5") {
-            "glm-4.5"
-        } else if model_display.contains("glm-4") {
-            "glm-4"
-        } else {
-            // 默认使用claude-3-5-sonnet的统计方式
-            "claude-3-5-sonnet-20241022"
+        Self {
+            enabled,
+            options: UsageConfig::default(),
         }
     }
-    
-    // 根据模型类型计算token使用量
-    fn calculate_tokens_for_model(&self, usage: &crate::config::Usage, model_name: &str) -> u32 {
-        match model_name {
-            "glm-4.5" | "glm-4" => {
-                // GLM模型优先使用total_tokens，如果没有则计算input_tokens + output_tokens
-                if let Some(total) = usage.total_tokens {
-                    if total > 0 {
-                        return total;
-                    }
-                }
-                usage.input_tokens.unwrap_or(0) + usage.output_tokens.unwrap_or(0)
-            },
-            "claude-3-5-sonnet-20241022" | "claude-3-haiku-20240307" => {
-                // Claude模型使用输入token总和（包含缓存）
-                usage.input_tokens.unwrap_or(0)
-                    + usage.cache_creation_input_tokens.unwrap_or(0)
-                    + usage.cache_read_input_tokens.unwrap_or(0)
-            },
-            "gpt-4" | "gpt-3.5-turbo" => {
-                // GPT模型使用输入+输出token
-                usage.input_tokens.unwrap_or(0) + usage.output_tokens.unwrap_or(0)
-            },
-            _ => {
-                // 默认使用Claude的统计方式
-                usage.input_tokens.unwrap_or(0)
-                    + usage.cache_creation_input_tokens.unwrap_or(0)
-                    + usage.cache_read_input_tokens.unwrap_or(0)
-            }
+
+    pub fn from_config(config: &UsageConfig) -> Self {
+        Self {
+            enabled: config.enabled,
+            options: config.clone(),
         }
     }
-}
 
-impl Segment for UsageSegment {
-    fn render(&self, input: &InputData) -> String {
+    /// Render the segment along with how full the context window is.
+    pub fn render_with_severity(&self, input: &InputData) -> (String, Severity) {
         if !self.enabled {
-            return String::new();
+            return (String::new(), Severity::Normal);
         }
 
-        let context_used_token = parse_transcript_usage(&input.transcript_path);
-        let context_used_rate = (context_used_token as f64 / CONTEXT_LIMIT as f64) * 100.0;
+        let context = parse_transcript_usage(&input.transcript_path);
+        let context_used_token = context.tokens;
+        let context_used_ratio = context_used_token as f64 / CONTEXT_LIMIT as f64;
+        let context_used_rate = context_used_ratio * 100.0;
 
         // Format percentage: show integer when whole number, decimal when fractional
         let percentage_display = if context_used_rate.fract() == 0.0 {
@@ -95,10 +55,50 @@ impl Segment for UsageSegment {
             context_used_token.to_string()
         };
 
-        format!(
-            "\u{f49b} {} · {} tokens",
-            percentage_display, tokens_display
-        )
+        let bar = || render_bar(context_used_ratio, self.options.bar_width);
+        let mut content = match self.options.display {
+            UsageDisplay::Text => format!(
+                "\u{f49b} {} · {} tokens",
+                percentage_display, tokens_display
+            ),
+            UsageDisplay::Bar => format!("\u{f49b} {} {}", bar(), percentage_display),
+            UsageDisplay::Both => format!(
+                "\u{f49b} {} {} · {} tokens",
+                bar(),
+                percentage_display,
+                tokens_display
+            ),
+        };
+
+        if context.compactions > 0 {
+            content.push_str(&format!(" · \u{21bb}{}", context.compactions));
+        }
+
+        let severity = if context_used_ratio >= self.options.critical_threshold {
+            Severity::Critical
+        } else if context_used_ratio >= self.options.warning_threshold {
+            Severity::Warning
+        } else {
+            Severity::Normal
+        };
+
+        // Close to the point where Claude Code compacts the conversation on its own
+        if severity == Severity::Critical {
+            let until_compact = (self.options.auto_compact_threshold - context_used_ratio) * 100.0;
+            if until_compact > 0.0 {
+                content.push_str(&format!(" · \u{26a0} {:.0}% to compact", until_compact));
+            } else {
+                content.push_str(" · \u{26a0} compacting");
+            }
+        }
+
+        (content, severity)
+    }
+}
+
+impl Segment for UsageSegment {
+    fn render(&self, input: &InputData) -> String {
+        self.render_with_severity(input).0
     }
 
     fn enabled(&self) -> bool {
@@ -106,36 +106,59 @@ impl Segment for UsageSegment {
     }
 }
 
-fn parse_transcript_usage<P: AsRef<Path>>(transcript_path: P) -> u32 {
-    let file = match fs::File::open(&transcript_path) {
-        Ok(file) => file,
-        Err(_) => return 0,
-    };
-
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .collect::<Result<Vec<_>, _>>()
-        .unwrap_or_default();
-
-    for line in lines.iter().rev() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
+/// A bar of `width` cells filled with eighth-block characters, e.g. `████▌░░░░░`.
+fn render_bar(ratio: f64, width: usize) -> String {
+    const PARTIAL: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+    let eighths = (ratio.clamp(0.0, 1.0) * width as f64 * 8.0).round() as usize;
+    let full = eighths / 8;
+    let remainder = eighths % 8;
+
+    let mut bar = "█".repeat(full);
+    if remainder > 0 {
+        bar.push(PARTIAL[remainder]);
+    }
+    let used = full + usize::from(remainder > 0);
+    bar.push_str(&"░".repeat(width.saturating_sub(used)));
+    bar
+}
+
+/// Context state of the current session.
+#[derive(Debug, Default, PartialEq)]
+pub struct ContextUsage {
+    /// Tokens in the context window as of the latest assistant message
+    pub tokens: u32,
+    /// How many times the conversation has been compacted
+    pub compactions: usize,
+}
+
+pub fn parse_transcript_usage<P: AsRef<Path>>(transcript_path: P) -> ContextUsage {
+    let entries = transcript::read_entries(transcript_path);
+    let compactions =
+        transcript::count_compactions(entries.iter().filter(|entry| !entry.is_sidechain));
+
+    // Sub-agents run in their own context window, so only the main chain counts here
+    for entry in entries.iter().rev().filter(|entry| !entry.is_sidechain) {
+        // Nothing has been sent since the last compaction, so the old usage no longer applies.
+        // `summary` entries are only titles for /resume and don't mark a compaction.
+        if transcript::is_compact_boundary(entry) {
+            break;
         }
 
-        if let Ok(entry) = serde_json::from_str::<TranscriptEntry>(line) {
-            if entry.entry_type == "assistant" {
-                if let Some(message) = &entry.message {
-                    if let Some(usage) = &message.usage {
-                        return calculate_input_tokens(usage);
-                    }
-                }
+        if entry.entry_type == "assistant" {
+            if let Some(usage) = entry.message.as_ref().and_then(|m| m.usage.as_ref()) {
+                return ContextUsage {
+                    tokens: calculate_input_tokens(usage),
+                    compactions,
+                };
             }
         }
     }
 
-    0
+    ContextUsage {
+        tokens: 0,
+        compactions,
+    }
 }
 
 fn calculate_input_tokens(usage: &crate::config::Usage) -> u32 {
@@ -162,3 +185,52 @@ fn calculate_input_tokens(usage: &crate::config::Usage) -> u32 {
     // Priority 4: Input tokens only (last resort)
     usage.input_tokens.or(usage.prompt_tokens).unwrap_or(0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::transcript::TestTranscript;
+
+    #[test]
+    fn test_context_resets_after_compaction() {
+        let transcript = TestTranscript::new::<&str>(&[]);
+        let assistant = r#"{"type":"assistant","message":{"type":"message","role":"assistant","usage":{"input_tokens":150000}}}"#;
+        let boundary =
+            r#"{"type":"system","subtype":"compact_boundary","content":"Conversation compacted"}"#;
+        let summary = r#"{"type":"user","isCompactSummary":true,"message":{"role":"user","content":"This session is being continued..."}}"#;
+        let after = r#"{"type":"assistant","message":{"type":"message","role":"assistant","usage":{"input_tokens":12000}}}"#;
+
+        let usage_of = |lines: &[&str]| {
+            transcript.write(lines);
+            parse_transcript_usage(&transcript.path)
+        };
+
+        let compacted = usage_of(&[assistant, boundary, summary]);
+        let continued = usage_of(&[assistant, boundary, summary, after]);
+        // Older versions only write the flagged summary message
+        let twice = usage_of(&[assistant, summary, after, summary]);
+
+        assert_eq!((compacted.tokens, compacted.compactions), (0, 1));
+        assert_eq!((continued.tokens, continued.compactions), (12000, 1));
+        assert_eq!((twice.tokens, twice.compactions), (0, 2));
+    }
+
+    #[test]
+    fn test_context_ignores_sidechain() {
+        let main = r#"{"type":"assistant","isSidechain":false,"message":{"type":"message","role":"assistant","usage":{"input_tokens":90000}}}"#;
+        let agent = r#"{"type":"assistant","isSidechain":true,"parentUuid":"a1","message":{"type":"message","role":"assistant","usage":{"input_tokens":4000}}}"#;
+        let transcript = TestTranscript::new(&[main, agent]);
+        let usage = parse_transcript_usage(&transcript.path);
+
+        assert_eq!(usage.tokens, 90000);
+    }
+
+    #[test]
+    fn test_render_bar() {
+        assert_eq!(render_bar(0.0, 10), "░░░░░░░░░░");
+        assert_eq!(render_bar(0.45, 10), "████▌░░░░░");
+        assert_eq!(render_bar(0.5125, 10), "█████▏░░░░");
+        assert_eq!(render_bar(1.3, 4), "████");
+        assert_eq!(render_bar(0.5, 0), "");
+    }
+}
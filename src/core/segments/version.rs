@@ -0,0 +1,173 @@
+use super::{Segment, Severity};
+use crate::config::{InputData, VersionConfig};
+use std::cmp::Ordering;
+
+/// The Claude Code version, compared against the one pinned in the config.
+pub struct VersionSegment {
+    enabled: bool,
+    pinned: Option<String>,
+}
+
+impl VersionSegment {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            pinned: None,
+        }
+    }
+
+    pub fn from_config(config: &VersionConfig) -> Self {
+        Self::new(config.enabled).with_pinned(config.pinned.clone())
+    }
+
+    pub fn with_pinned(mut self, pinned: Option<String>) -> Self {
+        self.pinned = pinned;
+        self
+    }
+
+    /// Render the segment; running an older version than the pinned one is a warning.
+    pub fn render_with_severity(&self, input: &InputData) -> (String, Severity) {
+        if !self.enabled || input.version.is_empty() {
+            return (String::new(), Severity::Normal);
+        }
+
+        let mut content = format!("\u{f02b} v{}", input.version.trim_start_matches('v'));
+        let update = self
+            .pinned
+            .as_deref()
+            .filter(|pinned| compare_versions(&input.version, pinned) == Ordering::Less);
+        match update {
+            Some(pinned) => {
+                content.push_str(&format!(" \u{2191}{}", pinned.trim_start_matches('v')));
+                (content, Severity::Warning)
+            }
+            None => (content, Severity::Normal),
+        }
+    }
+}
+
+impl Segment for VersionSegment {
+    fn render(&self, input: &InputData) -> String {
+        self.render_with_severity(input).0
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+/// Compare dotted versions numerically; `1.0.9` < `1.0.10`, and a pre-release is older than
+/// its release (`2.0.0-beta.1` < `2.0.0`), as in semver. Build metadata is ignored.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn split(version: &str) -> (Vec<u64>, Option<&str>) {
+        let version = version.trim().trim_start_matches('v');
+        let version = version.split(['+', ' ']).next().unwrap_or_default();
+        let (release, pre) = match version.split_once('-') {
+            Some((release, pre)) => (release, Some(pre)),
+            None => (version, None),
+        };
+        let release = release
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect();
+        (release, pre)
+    }
+
+    /// Numeric identifiers compare numerically and sort before alphanumeric ones.
+    fn compare_pre(a: &str, b: &str) -> Ordering {
+        let (mut a, mut b) = (a.split('.'), b.split('.'));
+        loop {
+            match (a.next(), b.next()) {
+                (None, None) => return Ordering::Equal,
+                (None, Some(_)) => return Ordering::Less,
+                (Some(_), None) => return Ordering::Greater,
+                (Some(x), Some(y)) => {
+                    let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+                        (Ok(x), Ok(y)) => x.cmp(&y),
+                        (Ok(_), Err(_)) => Ordering::Less,
+                        (Err(_), Ok(_)) => Ordering::Greater,
+                        (Err(_), Err(_)) => x.cmp(y),
+                    };
+                    if ordering != Ordering::Equal {
+                        return ordering;
+                    }
+                }
+            }
+        }
+    }
+
+    let ((a, a_pre), (b, b_pre)) = (split(a), split(b));
+    let len = a.len().max(b.len());
+    let padded = |parts: &[u64]| -> Vec<u64> {
+        (0..len)
+            .map(|i| parts.get(i).copied().unwrap_or(0))
+            .collect()
+    };
+    padded(&a)
+        .cmp(&padded(&b))
+        .then_with(|| match (a_pre, b_pre) {
+            (None, None) => Ordering::Equal,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(a), Some(b)) => compare_pre(a, b),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_available() {
+        let input = InputData {
+            version: "1.0.9".to_string(),
+            ..InputData::default()
+        };
+
+        let segment = VersionSegment::new(true).with_pinned(Some("1.0.10".to_string()));
+        assert_eq!(
+            segment.render_with_severity(&input),
+            (
+                "\u{f02b} v1.0.9 \u{2191}1.0.10".to_string(),
+                Severity::Warning
+            )
+        );
+        let segment = VersionSegment::new(true).with_pinned(Some("v1.0".to_string()));
+        assert_eq!(
+            segment.render_with_severity(&input),
+            ("\u{f02b} v1.0.9".to_string(), Severity::Normal)
+        );
+    }
+
+    #[test]
+    fn test_pre_release_is_older() {
+        assert_eq!(compare_versions("2.0.0-beta.1", "2.0"), Ordering::Less);
+        assert_eq!(compare_versions("2.0.0", "2.0.0-rc.1"), Ordering::Greater);
+        assert_eq!(
+            compare_versions("2.0.0-beta.2", "2.0.0-beta.10"),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_versions("2.0.0-alpha", "2.0.0-alpha.1"),
+            Ordering::Less
+        );
+        assert_eq!(compare_versions("2.0.0-rc.1", "1.9.9"), Ordering::Greater);
+        assert_eq!(
+            compare_versions("1.0.80 (Claude Code)", "v1.0.80+build.5"),
+            Ordering::Equal
+        );
+
+        let input = InputData {
+            version: "2.0.0-beta.1".to_string(),
+            ..InputData::default()
+        };
+        let segment = VersionSegment::new(true).with_pinned(Some("2.0.0".to_string()));
+        assert_eq!(
+            segment.render_with_severity(&input),
+            (
+                "\u{f02b} v2.0.0-beta.1 \u{2191}2.0.0".to_string(),
+                Severity::Warning
+            )
+        );
+    }
+}
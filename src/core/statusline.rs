@@ -1,62 +1,196 @@
-use crate::config::{Config, InputData};
-use crate::core::segments::{DirectorySegment, GitSegment, ModelSegment, TimeSegment, UsageSegment, CostSegment, Segment};
+use crate::config::{Config, InputData, TimeConfig};
+use crate::core::aggregate::Period;
+use crate::core::segments::git::{GitInfo, GitSource};
+use crate::core::segments::{
+    BlockSegment, CostSegment, DirectorySegment, GitSegment, LinesSegment, ModelSegment,
+    OutputStyleSegment, Segment, SessionSegment, SpendSegment, TimeSegment, UsageSegment,
+    VersionSegment,
+};
+use crate::core::theme::{self, paint, Theme};
+use crate::core::trace;
+use std::fmt::Debug;
 
 pub struct StatusLineGenerator {
     config: Config,
+    theme: Theme,
+    git_source: GitSource,
 }
 
 impl StatusLineGenerator {
     pub fn new(config: Config) -> Self {
-        Self { config }
+        let theme = Theme::by_name(&config.theme).unwrap_or_else(|| {
+            trace::event("config", || {
+                format!("unknown theme {:?}, using dark", config.theme)
+            });
+            theme::DARK
+        });
+        Self {
+            config,
+            theme,
+            git_source: GitSource::Live,
+        }
+    }
+
+    /// Repository state for `input`, read the way the git segment reads it plus the commit
+    /// hash, so `--record` can save the state the statusline is rendered with.
+    pub fn git_info(&self, input: &InputData) -> Option<GitInfo> {
+        match &self.git_source {
+            GitSource::Live => GitSegment::from_config(&self.config.segments.git)
+                .with_sha(true)
+                .get_git_info(&input.workspace.current_dir),
+            GitSource::Snapshot(snapshot) => snapshot.clone(),
+        }
+    }
+
+    /// Render git state recorded earlier instead of asking git (see `ccline replay`).
+    pub fn with_git_snapshot(mut self, snapshot: Option<GitInfo>) -> Self {
+        self.git_source = GitSource::Snapshot(snapshot);
+        self
     }
 
     pub fn generate(&self, input: &InputData) -> String {
+        let theme = &self.theme;
         let mut segments = Vec::new();
 
         // Assemble segments with proper colors
-        if self.config.segments.model {
-            let model_segment = ModelSegment::new(true);
-            let content = model_segment.render(input);
-            segments.push(format!("\x1b[1;36m{}\x1b[0m", content));
+        // Segments whose input is missing from the payload are left out
+        let has_model = !input.model.id.is_empty() || !input.model.display_name.is_empty();
+        if self.config.segments.model.enabled && has_model {
+            let model_segment = ModelSegment::from_config(&self.config.segments.model);
+            let content = timed("model", || model_segment.render(input));
+            segments.push(paint(theme.model, &content));
+        }
+
+        if self.config.segments.output_style.enabled {
+            let style_segment = OutputStyleSegment::from_config(&self.config.segments.output_style);
+            let content = timed("output_style", || style_segment.render(input));
+            if !content.is_empty() {
+                segments.push(paint(theme.output_style, &content));
+            }
         }
 
-        if self.config.segments.directory {
-            let dir_segment = DirectorySegment::new(true);
-            let content = dir_segment.render(input);
+        if self.config.segments.directory.enabled && !input.workspace.current_dir.is_empty() {
+            let dir_segment = DirectorySegment::from_config(&self.config.segments.directory);
+            let (content, severity) =
+                timed("directory", || dir_segment.render_with_severity(input));
             // Extract directory name without icon
             let dir_name = content.trim_start_matches('\u{f024b}').trim_start();
             segments.push(format!(
-                "\x1b[1;33m\u{f024b}\x1b[0m \x1b[1;32m{}\x1b[0m",
-                dir_name
+                "{} {}",
+                paint(theme.directory_icon, "\u{f024b}"),
+                paint(theme.directory.get(severity), dir_name)
             ));
         }
 
-        if self.config.segments.git {
-            let git_segment = GitSegment::new(true);
-            let git_output = git_segment.render(input);
+        if self.config.segments.git.enabled {
+            let git_segment = GitSegment::from_config(&self.config.segments.git)
+                .with_source(self.git_source.clone());
+            let git_output = timed("git", || git_segment.render(input));
             if !git_output.is_empty() {
-                segments.push(format!("\x1b[1;34m{}\x1b[0m", git_output));
+                segments.push(paint(theme.git, &git_output));
             }
         }
 
-        if self.config.segments.time {
-            let time_segment = TimeSegment::new(true);
-            let content = time_segment.render(input);
-            segments.push(format!("\x1b[1;36m{}\x1b[0m", content));
+        if self.config.segments.lines.enabled {
+            let lines_segment = LinesSegment::from_config(&self.config.segments.lines);
+            let content = timed("lines", || lines_segment.render(input));
+            if !content.is_empty() {
+                segments.push(paint(theme.lines, &content));
+            }
+        }
+
+        if self.config.segments.time.enabled {
+            let time_config = &self.config.segments.time;
+            let time_segment = TimeSegment::from_config(time_config);
+            let content = timed("time", || time_segment.render(input));
+            segments.push(paint(theme.time, &content));
+
+            // Extra clocks share the main clock's 12/24-hour and seconds settings
+            for clock in &time_config.clocks {
+                let clock_segment = TimeSegment::from_config(&TimeConfig {
+                    format: clock.format.clone().or(time_config.format.clone()),
+                    timezone: Some(clock.timezone.clone()),
+                    label: clock.label.clone(),
+                    clocks: Vec::new(),
+                    ..time_config.clone()
+                });
+                let content = timed("clock", || clock_segment.render(input));
+                segments.push(paint(theme.time, &content));
+            }
+        }
+
+        if self.config.segments.session.enabled {
+            let session_segment = SessionSegment::from_config(&self.config.segments.session);
+            let content = timed("session", || session_segment.render(input));
+            if !content.is_empty() {
+                segments.push(paint(theme.session, &content));
+            }
         }
 
-        if self.config.segments.usage {
-            let usage_segment = UsageSegment::new(true);
-            let content = usage_segment.render(input);
-            segments.push(format!("\x1b[1;35m{}\x1b[0m", content));
+        if self.config.segments.usage.enabled && !input.transcript_path.is_empty() {
+            let usage_segment = UsageSegment::from_config(&self.config.segments.usage);
+            let (content, severity) = timed("usage", || usage_segment.render_with_severity(input));
+            segments.push(paint(theme.usage.get(severity), &content));
         }
 
-        if self.config.segments.cost {
-            let cost_segment = CostSegment::new(true);
-            let content = cost_segment.render(input);
-            segments.push(format!("\x1b[1;33m{}\x1b[0m", content));
+        if self.config.segments.cost.enabled && !input.transcript_path.is_empty() {
+            let cost_segment = CostSegment::from_config(&self.config.segments.cost)
+                .with_budget(self.config.budget.clone());
+            let (content, severity) = timed("cost", || cost_segment.render_with_severity(input));
+            segments.push(paint(theme.cost.get(severity), &content));
         }
-        // Join segments with white separator
-        segments.join("\x1b[37m | \x1b[0m")
+
+        if self.config.segments.spend.enabled {
+            let spend_config = &self.config.segments.spend;
+            let periods = [
+                (spend_config.today, Period::Today),
+                (spend_config.week, Period::Week),
+                (spend_config.month, Period::Month),
+            ]
+            .into_iter()
+            .filter_map(|(enabled, period)| enabled.then_some(period))
+            .collect();
+            let spend_segment = SpendSegment::new(true).with_periods(periods);
+            let content = timed("spend", || spend_segment.render(input));
+            if !content.is_empty() {
+                segments.push(paint(theme.spend, &content));
+            }
+        }
+
+        if self.config.segments.block.enabled {
+            let block_segment = BlockSegment::from_config(&self.config.segments.block);
+            let content = timed("block", || block_segment.render(input));
+            if !content.is_empty() {
+                segments.push(paint(theme.block, &content));
+            }
+        }
+
+        if self.config.segments.version.enabled {
+            let version_segment = VersionSegment::from_config(&self.config.segments.version);
+            let (content, severity) =
+                timed("version", || version_segment.render_with_severity(input));
+            if !content.is_empty() {
+                segments.push(paint(theme.version.get(severity), &content));
+            }
+        }
+
+        segments.join(&paint(theme.separator, " | "))
+    }
+
+    /// Append the `⚠` marker for a stdin payload that couldn't be fully read or a command
+    /// line override that couldn't be applied, if enabled.
+    pub fn mark_input_error(&self, statusline: &mut String) {
+        if !self.config.show_input_errors {
+            return;
+        }
+        if !statusline.is_empty() {
+            statusline.push_str(&paint(self.theme.separator, " | "));
+        }
+        statusline.push_str(&paint(self.theme.error, "\u{26a0}"));
     }
 }
+
+/// Render one segment, tracing its output and how long it took.
+fn timed<T: Debug>(name: &str, render: impl FnOnce() -> T) -> T {
+    trace::timed("segment", render, |output| format!("{} {:?}", name, output))
+}
@@ -0,0 +1,171 @@
+use crate::core::segments::Severity;
+
+/// ANSI SGR parameters such as `1;36`; empty means no styling.
+pub type Style = &'static str;
+
+/// One style per level, for segments that change color with their severity.
+#[derive(Debug, Clone, Copy)]
+pub struct SeverityStyles {
+    pub normal: Style,
+    pub warning: Style,
+    pub critical: Style,
+}
+
+impl SeverityStyles {
+    pub fn get(&self, severity: Severity) -> Style {
+        match severity {
+            Severity::Normal => self.normal,
+            Severity::Warning => self.warning,
+            Severity::Critical => self.critical,
+        }
+    }
+}
+
+/// Colors of every part of the statusline.
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub name: &'static str,
+    pub model: Style,
+    pub output_style: Style,
+    pub directory_icon: Style,
+    /// Warning when the current directory is outside the project
+    pub directory: SeverityStyles,
+    pub git: Style,
+    pub lines: Style,
+    pub time: Style,
+    pub session: Style,
+    pub usage: SeverityStyles,
+    pub cost: SeverityStyles,
+    pub spend: Style,
+    pub block: Style,
+    /// Warning when a newer version is pinned in the config
+    pub version: SeverityStyles,
+    pub separator: Style,
+    /// The `⚠` shown when the stdin payload couldn't be read
+    pub error: Style,
+}
+
+pub const DARK: Theme = Theme {
+    name: "dark",
+    model: "1;36",
+    output_style: "1;35",
+    directory_icon: "1;33",
+    directory: SeverityStyles {
+        normal: "1;32",
+        warning: "1;38;5;208",
+        critical: "1;31",
+    },
+    git: "1;34",
+    lines: "1;32",
+    time: "1;36",
+    session: "1;36",
+    usage: SeverityStyles {
+        normal: "1;32",
+        warning: "1;33",
+        critical: "1;31",
+    },
+    cost: SeverityStyles {
+        normal: "1;33",
+        warning: "1;38;5;208",
+        // Blinking red once a budget is used up
+        critical: "1;5;31",
+    },
+    spend: "1;33",
+    block: "1;33",
+    version: SeverityStyles {
+        normal: "37",
+        warning: "1;38;5;208",
+        critical: "1;31",
+    },
+    separator: "37",
+    error: "1;31",
+};
+
+/// Darker 256-color shades that stay readable on a light background.
+pub const LIGHT: Theme = Theme {
+    name: "light",
+    model: "1;38;5;25",
+    output_style: "38;5;90",
+    directory_icon: "38;5;130",
+    directory: SeverityStyles {
+        normal: "1;38;5;28",
+        warning: "1;38;5;166",
+        critical: "1;38;5;160",
+    },
+    git: "1;38;5;54",
+    lines: "38;5;28",
+    time: "38;5;25",
+    session: "38;5;25",
+    usage: SeverityStyles {
+        normal: "1;38;5;28",
+        warning: "1;38;5;130",
+        critical: "1;38;5;160",
+    },
+    cost: SeverityStyles {
+        normal: "1;38;5;94",
+        warning: "1;38;5;166",
+        critical: "1;5;38;5;160",
+    },
+    spend: "38;5;94",
+    block: "38;5;94",
+    version: SeverityStyles {
+        normal: "38;5;244",
+        warning: "1;38;5;166",
+        critical: "1;38;5;160",
+    },
+    separator: "38;5;244",
+    error: "1;38;5;160",
+};
+
+/// No escape sequences at all, for terminals without color.
+pub const PLAIN: Theme = Theme {
+    name: "plain",
+    model: "",
+    output_style: "",
+    directory_icon: "",
+    directory: SeverityStyles {
+        normal: "",
+        warning: "",
+        critical: "",
+    },
+    git: "",
+    lines: "",
+    time: "",
+    session: "",
+    usage: SeverityStyles {
+        normal: "",
+        warning: "",
+        critical: "",
+    },
+    cost: SeverityStyles {
+        normal: "",
+        warning: "",
+        critical: "",
+    },
+    spend: "",
+    block: "",
+    version: SeverityStyles {
+        normal: "",
+        warning: "",
+        critical: "",
+    },
+    separator: "",
+    error: "",
+};
+
+pub const THEMES: [Theme; 3] = [DARK, LIGHT, PLAIN];
+
+impl Theme {
+    pub fn by_name(name: &str) -> Option<Theme> {
+        THEMES.into_iter().find(|theme| theme.name == name)
+    }
+}
+
+/// Wrap `text` in the escape sequence for `style`.
+pub fn paint(style: Style, text: &str) -> String {
+    if style.is_empty() {
+        text.to_string()
+    } else {
+        format!("\x1b[{}m{}\x1b[0m", style, text)
+    }
+}
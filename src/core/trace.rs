@@ -0,0 +1,157 @@
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// One step of a render, recorded for `--debug`/`CCLINE_LOG` and `--explain`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceEvent {
+    /// Microseconds since tracing was enabled
+    pub at_us: u64,
+    /// `stdin`, `config`, `segment`, `git`, `transcript`, ...
+    pub kind: &'static str,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_us: Option<u64>,
+}
+
+struct Trace {
+    started: Instant,
+    events: Vec<TraceEvent>,
+}
+
+static TRACE: Mutex<Option<Trace>> = Mutex::new(None);
+
+/// Start recording events. Until this is called every other function here is a no-op.
+pub fn enable() {
+    if let Ok(mut trace) = TRACE.lock() {
+        trace.get_or_insert_with(|| Trace {
+            started: Instant::now(),
+            events: Vec::new(),
+        });
+    }
+}
+
+pub fn is_enabled() -> bool {
+    TRACE.lock().is_ok_and(|trace| trace.is_some())
+}
+
+/// Record an event; `message` is only built while tracing.
+pub fn event(kind: &'static str, message: impl FnOnce() -> String) {
+    record(kind, message, None);
+}
+
+/// Run `f` and record how long it took, described by `describe` from its result.
+pub fn timed<T>(
+    kind: &'static str,
+    f: impl FnOnce() -> T,
+    describe: impl FnOnce(&T) -> String,
+) -> T {
+    if !is_enabled() {
+        return f();
+    }
+    let started = Instant::now();
+    let result = f();
+    record(kind, || describe(&result), Some(started.elapsed()));
+    result
+}
+
+fn record(kind: &'static str, message: impl FnOnce() -> String, duration: Option<Duration>) {
+    let Ok(mut guard) = TRACE.lock() else {
+        return;
+    };
+    if let Some(trace) = guard.as_mut() {
+        let at = trace
+            .started
+            .elapsed()
+            .saturating_sub(duration.unwrap_or_default());
+        trace.events.push(TraceEvent {
+            at_us: at.as_micros() as u64,
+            kind,
+            message: message(),
+            duration_us: duration.map(|duration| duration.as_micros() as u64),
+        });
+    }
+}
+
+/// Events recorded so far in the order they started, leaving the trace empty.
+///
+/// Timed events start before the events recorded inside them, e.g. a segment before
+/// the git commands it ran.
+pub fn take() -> Vec<TraceEvent> {
+    let mut events = TRACE
+        .lock()
+        .ok()
+        .and_then(|mut guard| {
+            guard
+                .as_mut()
+                .map(|trace| std::mem::take(&mut trace.events))
+        })
+        .unwrap_or_default();
+    events.sort_by_key(|event| event.at_us);
+    events
+}
+
+/// Append the events to `path` as JSON lines, one object per event.
+pub fn write_log(path: &Path, events: &[TraceEvent]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let run = chrono::Local::now().to_rfc3339();
+    for event in events {
+        let line = serde_json::json!({ "run": run, "event": event });
+        writeln!(file, "{}", line)?;
+    }
+    Ok(())
+}
+
+/// Human-readable trace, one event per line.
+pub fn format_events(events: &[TraceEvent]) -> String {
+    let mut out = String::new();
+    for event in events {
+        let duration = event
+            .duration_us
+            .map(|us| format!("{:.2}ms", us as f64 / 1000.0))
+            .unwrap_or_default();
+        let _ = writeln!(
+            out,
+            "{:>9.2}ms  {:<10} {:>9}  {}",
+            event.at_us as f64 / 1000.0,
+            event.kind,
+            duration,
+            event.message
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_events() {
+        let events = [
+            TraceEvent {
+                at_us: 1500,
+                kind: "segment",
+                message: "git".to_string(),
+                duration_us: Some(6250),
+            },
+            TraceEvent {
+                at_us: 1700,
+                kind: "stdin",
+                message: "{}".to_string(),
+                duration_us: None,
+            },
+        ];
+        assert_eq!(
+            format_events(&events),
+            "     1.50ms  segment       6.25ms  git\n     1.70ms  stdin                 {}\n"
+        );
+    }
+}
@@ -0,0 +1,243 @@
+use crate::config::TranscriptEntry;
+use crate::core::trace;
+use chrono::{DateTime, Utc};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+
+static PARSED_LINES: AtomicUsize = AtomicUsize::new(0);
+static SKIPPED_LINES: AtomicUsize = AtomicUsize::new(0);
+
+/// Transcript lines read so far by this process.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseStats {
+    pub parsed: usize,
+    /// Non-blank lines that weren't a JSON object we could read
+    pub skipped: usize,
+}
+
+pub fn parse_stats() -> ParseStats {
+    ParseStats {
+        parsed: PARSED_LINES.load(Ordering::Relaxed),
+        skipped: SKIPPED_LINES.load(Ordering::Relaxed),
+    }
+}
+
+/// Read every parseable entry from a transcript file, in file order.
+///
+/// Blank and malformed lines are skipped (and counted, see [`parse_stats`]); a missing
+/// file yields no entries.
+pub fn read_entries<P: AsRef<Path>>(transcript_path: P) -> Vec<TranscriptEntry> {
+    let path = transcript_path.as_ref();
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) => {
+            trace::event("transcript", || format!("{}: {}", path.display(), e));
+            return Vec::new();
+        }
+    };
+
+    let skipped = Cell::new(0);
+    let entries = trace::timed(
+        "transcript",
+        || {
+            BufReader::new(file)
+                .lines()
+                .map_while(Result::ok)
+                .filter_map(|line| {
+                    let line = line.trim();
+                    if line.is_empty() {
+                        return None;
+                    }
+                    let entry = serde_json::from_str::<TranscriptEntry>(line).ok();
+                    if entry.is_none() {
+                        skipped.set(skipped.get() + 1);
+                    }
+                    entry
+                })
+                .collect()
+        },
+        |entries: &Vec<TranscriptEntry>| {
+            format!(
+                "{}: {} lines parsed, {} skipped",
+                path.display(),
+                entries.len(),
+                skipped.get()
+            )
+        },
+    );
+
+    PARSED_LINES.fetch_add(entries.len(), Ordering::Relaxed);
+    SKIPPED_LINES.fetch_add(skipped.get(), Ordering::Relaxed);
+    entries
+}
+
+/// Keep one entry per API response.
+///
+/// A streamed response is written as one line per content block, each repeating the
+/// same `message.id`, `requestId` and usage. Only the last line of each response is
+/// kept since it carries the final output token count; entries without a message id
+/// are left alone.
+pub fn dedupe_responses(entries: Vec<TranscriptEntry>) -> Vec<TranscriptEntry> {
+    let response_key = |entry: &TranscriptEntry| {
+        let id = entry.message.as_ref()?.id.clone()?;
+        Some((id, entry.request_id.clone()))
+    };
+
+    let mut last_line = HashMap::new();
+    for (index, entry) in entries.iter().enumerate() {
+        if let Some(key) = response_key(entry) {
+            last_line.insert(key, index);
+        }
+    }
+
+    entries
+        .into_iter()
+        .enumerate()
+        .filter(|(index, entry)| response_key(entry).is_none_or(|key| last_line[&key] == *index))
+        .map(|(_, entry)| entry)
+        .collect()
+}
+
+/// Whether the entry marks a point where the conversation was compacted.
+///
+/// Newer Claude Code versions write a `system` entry with subtype `compact_boundary`
+/// followed by the summary message; older ones only write the flagged summary message.
+pub fn is_compact_boundary(entry: &TranscriptEntry) -> bool {
+    (entry.entry_type == "system" && entry.subtype.as_deref() == Some("compact_boundary"))
+        || entry.is_compact_summary
+}
+
+/// Number of times the conversation in `entries` has been compacted.
+pub fn count_compactions<'a>(entries: impl IntoIterator<Item = &'a TranscriptEntry>) -> usize {
+    let mut count = 0;
+    let mut previous_was_boundary = false;
+    for entry in entries {
+        let is_boundary = is_compact_boundary(entry);
+        // A boundary entry and the summary message right after it are one compaction
+        if is_boundary && !(previous_was_boundary && entry.is_compact_summary) {
+            count += 1;
+        }
+        previous_was_boundary = is_boundary;
+    }
+    count
+}
+
+/// Parse an RFC 3339 transcript timestamp such as `2025-08-12T09:30:00.123Z`.
+pub fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// `~/.claude/projects`, where Claude Code keeps one directory of transcripts per project.
+pub fn projects_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claude").join("projects"))
+}
+
+/// All `.jsonl` transcripts under the projects directory, optionally only those
+/// modified at or after `modified_since`.
+pub fn find_transcripts(modified_since: Option<SystemTime>) -> Vec<PathBuf> {
+    let Some(projects_dir) = projects_dir() else {
+        return Vec::new();
+    };
+    let Ok(projects) = fs::read_dir(projects_dir) else {
+        return Vec::new();
+    };
+
+    let mut transcripts = Vec::new();
+    for project in projects.flatten() {
+        let Ok(files) = fs::read_dir(project.path()) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let path = file.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("jsonl") {
+                continue;
+            }
+            if let Some(since) = modified_since {
+                let modified = file.metadata().and_then(|m| m.modified());
+                if modified.is_ok_and(|modified| modified < since) {
+                    continue;
+                }
+            }
+            transcripts.push(path);
+        }
+    }
+
+    transcripts
+}
+
+/// A transcript file for tests, with a path unique to the process and call, removed on drop.
+#[cfg(test)]
+pub(crate) struct TestTranscript {
+    pub path: PathBuf,
+}
+
+#[cfg(test)]
+impl TestTranscript {
+    pub fn new<S: AsRef<str>>(lines: &[S]) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+        let name = format!(
+            "ccline_test_{}_{}.jsonl",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        );
+        let transcript = Self {
+            path: std::env::temp_dir().join(name),
+        };
+        transcript.write(lines);
+        transcript
+    }
+
+    /// Replace the contents with `lines`.
+    pub fn write<S: AsRef<str>>(&self, lines: &[S]) {
+        let lines: Vec<&str> = lines.iter().map(AsRef::as_ref).collect();
+        fs::write(&self.path, lines.join("\n")).unwrap();
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestTranscript {
+    fn drop(&mut self) {
+        fs::remove_file(&self.path).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tolerant_entries() {
+        let lines = [
+            r#"{"type":"summary","summary":"Fix the parser","leafUuid":"a1"}"#,
+            r#"{"type":"file-history-snapshot","messageId":"m1","snapshot":{}}"#,
+            r#"{"type":"user","message":{"role":"user","content":"plain string prompt"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"hi"},42,{"type":"tool_use","name":"Edit","input":{}}],"usage":{"input_tokens":10}}}"#,
+            r#"not json"#,
+            r#"[1, 2]"#,
+        ];
+        let transcript = TestTranscript::new(&lines);
+        let before = parse_stats();
+        let entries = read_entries(&transcript.path);
+        let after = parse_stats();
+
+        assert_eq!(entries.len(), 4);
+        let prompt = entries[2].message.as_ref().unwrap();
+        assert_eq!(
+            prompt.content[0].text.as_deref(),
+            Some("plain string prompt")
+        );
+        let reply = entries[3].message.as_ref().unwrap();
+        assert_eq!(reply.content.len(), 2);
+        assert!(reply.usage.is_some());
+        // Other tests may read transcripts concurrently, so only a lower bound holds
+        assert!(after.skipped - before.skipped >= 2);
+    }
+}
@@ -1,10 +1,39 @@
-use ccometixline::cli::Cli;
-use ccometixline::config::{Config, ConfigLoader, InputData};
-use ccometixline::core::StatusLineGenerator;
-use std::io;
+use ccometixline::cli::{Cli, Commands};
+use ccometixline::commands;
+use ccometixline::config::{Config, InputData};
+use ccometixline::core::{capture, log, trace, transcript, StatusLineGenerator};
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::ExitCode;
 
-fn main() -> io::Result<()> {
-    let cli = Cli::parse_args();
+/// How much of an unreadable payload goes into the log
+const MAX_LOGGED_INPUT: usize = 4096;
+
+fn main() -> ExitCode {
+    match run(Cli::parse_args()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> io::Result<()> {
+    if let Some(command) = &cli.command {
+        return match command {
+            Commands::Report(args) => commands::report::run(args),
+            Commands::Preview(args) => commands::preview::run(args, load_config(&cli).0),
+            Commands::Replay(args) => commands::replay::run(args, load_config(&cli).0),
+            Commands::Install(args) => commands::install::install(args),
+            Commands::Uninstall(args) => commands::install::uninstall(args),
+            Commands::Doctor => commands::doctor::run(&cli),
+            Commands::Completions(args) => commands::completions::completions(args),
+            Commands::Man(args) => commands::completions::man(args),
+        };
+    }
 
     // Handle special CLI modes
     if cli.print_config {
@@ -23,18 +52,95 @@ fn main() -> io::Result<()> {
         return Ok(());
     }
 
-    // Load configuration
-    let config = ConfigLoader::load();
+    let debug_log = debug_log_path(&cli);
+    if debug_log.is_some() || cli.explain.is_some() {
+        trace::enable();
+    }
+
+    let (config, config_problems) = load_config(&cli);
 
-    // Read Claude Code data from stdin
-    let stdin = io::stdin();
-    let input: InputData = serde_json::from_reader(stdin.lock())?;
+    // Read Claude Code data from stdin; a bad payload still gets a statusline
+    let mut raw_input = String::new();
+    let read_error = match &cli.explain {
+        Some(path) => fs::read_to_string(path)
+            .map(|content| raw_input = content)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+            .err(),
+        None => io::stdin().read_to_string(&mut raw_input).err(),
+    };
+    trace::event("stdin", || raw_input.clone());
+    let (input, parse_error) = InputData::parse_lenient(&raw_input);
+    let input_error = read_error.map(|e| e.to_string()).or(parse_error);
+    if let Some(error) = &input_error {
+        trace::event("stdin", || format!("could not be fully read: {}", error));
+        let logged: String = raw_input.chars().take(MAX_LOGGED_INPUT).collect();
+        log::append(&format!(
+            "could not read stdin payload: {}: {:?}",
+            error, logged
+        ));
+    }
+
+    let mut generator = StatusLineGenerator::new(config);
+    if let Some(dir) = &cli.record {
+        // Git runs once; the statusline renders the same state the capture saves
+        let git = generator.git_info(&input);
+        // Recording is a side channel; the statusline still renders if it fails
+        match capture::record(dir, &raw_input, &input, git.as_ref()) {
+            Ok(path) => trace::event("record", || format!("saved {}", path.display())),
+            Err(e) => log::append(&format!("could not record to {}: {}", dir.display(), e)),
+        }
+        generator = generator.with_git_snapshot(git);
+    }
 
     // Generate statusline
-    let generator = StatusLineGenerator::new(config);
-    let statusline = generator.generate(&input);
+    let mut statusline = generator.generate(&input);
+    if input_error.is_some() || !config_problems.is_empty() {
+        generator.mark_input_error(&mut statusline);
+    }
 
     println!("{}", statusline);
 
+    let stats = transcript::parse_stats();
+    trace::event("transcript", || {
+        format!(
+            "{} lines parsed, {} skipped in total",
+            stats.parsed, stats.skipped
+        )
+    });
+    let events = trace::take();
+    if cli.explain.is_some() {
+        println!();
+        print!("{}", trace::format_events(&events));
+    }
+    if let Some(path) = debug_log {
+        // The statusline is already out; a log that can't be written only gets a note
+        if let Err(e) = trace::write_log(&path, &events) {
+            eprintln!("Failed to write debug log {}: {}", path.display(), e);
+        }
+    }
+
     Ok(())
 }
+
+/// The config with command line overrides applied; what couldn't be applied goes to stderr
+/// and the log, since Claude Code doesn't show stderr.
+fn load_config(cli: &Cli) -> (Config, Vec<String>) {
+    let (config, problems) = cli.load_config();
+    for problem in &problems {
+        eprintln!("{}", problem);
+        log::append(problem);
+    }
+    (config, problems)
+}
+
+/// Where `--debug` or `CCLINE_LOG` sends the render trace: `CCLINE_LOG=<path>` picks the
+/// file, `CCLINE_LOG=1` (or `--debug`) the default `~/.claude/ccline/debug.jsonl`.
+fn debug_log_path(cli: &Cli) -> Option<PathBuf> {
+    let from_env = env::var_os("CCLINE_LOG").filter(|value| !value.is_empty() && value != "0");
+    match from_env {
+        Some(value) if value != "1" && value != "true" => Some(PathBuf::from(value)),
+        Some(_) => log::debug_log_path(),
+        None if cli.debug => log::debug_log_path(),
+        None => None,
+    }
+}